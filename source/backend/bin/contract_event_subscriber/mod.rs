@@ -74,34 +74,64 @@ use clap::crate_description;
 use clap::crate_name;
 use clap::crate_version;
 use clap::value_t_or_exit;
+use clap::values_t_or_exit;
 use clap::App;
 use clap::AppSettings;
 use clap::Arg;
+use clap::ArgMatches;
 use clap::SubCommand;
-use infrastructure::bb8::Pool;
-use infrastructure::bb8_redis::RedisConnectionManager;
-use infrastructure::redis::AsyncCommands;
+use infrastructure::web3::ethabi::Contract;
+use infrastructure::web3::futures::future;
+use infrastructure::web3::futures::stream;
+use infrastructure::web3::futures::stream::BoxStream;
 use infrastructure::web3::futures::StreamExt;
+use infrastructure::web3::transports::WebSocket;
 use infrastructure::web3::types::Address;
 use infrastructure::web3::types::Log;
+use infrastructure::web3::types::H256;
+use infrastructure::web3::Error as Web3Error;
+use infrastructure::web3::Web3;
+use infrastructure::BlockHeaderSubscriptionCreator;
+use infrastructure::CollectionRegistryReader;
+use infrastructure::Configuration;
+use infrastructure::ConfigurationLoader;
 use infrastructure::ConnectionPoolCreator;
+use infrastructure::ConnectionState;
+use infrastructure::ContractAbiLoader;
+use infrastructure::ContractEventDecoder;
+use infrastructure::ContractLogLoader;
 use infrastructure::ContractSubscriptionCreator;
-use infrastructure::StorageKeyManager;
+use infrastructure::Error;
+use infrastructure::EventCheckpoint;
+use infrastructure::EventRecord;
+use infrastructure::EventStatus;
+use infrastructure::EventStore;
+use infrastructure::EventTopicCreator;
+use infrastructure::EventType;
+use infrastructure::ReconnectionBackoff;
+use infrastructure::RedisEventStore;
+use infrastructure::SqlConnectionPoolCreator;
+use infrastructure::SqlEventStore;
+use infrastructure::StorageBackend;
+use infrastructure::TokenOwnership;
 use infrastructure::Web3ConnectionCreator;
+use std::cmp;
 use std::convert::From;
-use std::error::Error;
+use std::path::Path;
 use std::str::FromStr;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::UnboundedSender;
 
+const BACKFILL_PAGE_BLOCK_QUANTITY: u64 = 1000;
+const COMMAND_ARGUMENT_ARTIFACT: &'static str = "artifact";
+const COMMAND_ARGUMENT_CONFIRMATION_DEPTH: &'static str = "confirmation_depth";
 const COMMAND_ARGUMENT_CONTRACT_ADDRESS: &'static str = "contract_address";
-const COMMAND_SUBSCRIBE_FOR_COLLECTION_CREATED_EVENT: &'static str =
-    "subscribe_for_collection_created_event";
-const COMMAND_SUBSCRIBE_FOR_TOKEN_MINTED_EVENT: &'static str = "subscribe_for_token_minted_event";
-// keccak_256 hash.
-const CONTRACT_EVENT_COLLECTION_CREATED_HASH: &'static str =
-    "3454b57f2dca4f5a54e8358d096ac9d1a0d2dab98991ddb89ff9ea1746260617";
-// keccak_256 hash.
-const CONTRACT_EVENT_TOKEN_MINTED_HASH: &'static str =
-    "c9fee7cd4889f66f10ff8117316524260a5242e88e25e0656dfb3f4196a21917";
+const COMMAND_ARGUMENT_FROM_BLOCK: &'static str = "from_block";
+const COMMAND_ARGUMENT_EVENT_NAME: &'static str = "event_name";
+const COMMAND_MIGRATE_TO_STREAMS: &'static str = "migrate-to-streams";
+const COMMAND_SUBSCRIBE: &'static str = "subscribe";
+const COMMAND_SUBSCRIBE_ALL: &'static str = "subscribe-all";
 
 fn main() -> () {
     let matches = App::new(crate_name!())
@@ -109,194 +139,1497 @@ fn main() -> () {
         .version(crate_version!())
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .subcommand(
-            SubCommand::with_name(COMMAND_SUBSCRIBE_FOR_COLLECTION_CREATED_EVENT)
-                .about("Listen for 'CollectionCreated' event.")
+            SubCommand::with_name(COMMAND_SUBSCRIBE)
+                .about("Listen for the named events of the contract.")
                 .arg(
                     Arg::with_name(COMMAND_ARGUMENT_CONTRACT_ADDRESS)
                         .index(1)
                         .takes_value(true)
                         .required(true)
                         .help("Contract address."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_EVENT_NAME)
+                        .index(2)
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Event name from the contract ABI, like 'CollectionCreated'."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_ARTIFACT)
+                        .long("artifact")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Truffle artifact of the contract. Default is the 'contract.metadata_file_path'."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_FROM_BLOCK)
+                        .long("from-block")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Block number to load historical events from before listening."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_CONFIRMATION_DEPTH)
+                        .long("confirmation-depth")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Quantity of blocks after which an event is final. Default is 0."),
                 ),
         )
         .subcommand(
-            SubCommand::with_name(COMMAND_SUBSCRIBE_FOR_TOKEN_MINTED_EVENT)
-                .about("Listen for 'TokenCreated' event.")
+            SubCommand::with_name(COMMAND_SUBSCRIBE_ALL)
+                .about("Listen for all events of the contract with one subscription.")
                 .arg(
                     Arg::with_name(COMMAND_ARGUMENT_CONTRACT_ADDRESS)
                         .index(1)
                         .takes_value(true)
                         .required(true)
                         .help("Contract address."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_ARTIFACT)
+                        .long("artifact")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Truffle artifact of the contract. Default is the 'contract.metadata_file_path'."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_FROM_BLOCK)
+                        .long("from-block")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Block number to load historical events from before listening."),
+                )
+                .arg(
+                    Arg::with_name(COMMAND_ARGUMENT_CONFIRMATION_DEPTH)
+                        .long("confirmation-depth")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Quantity of blocks after which an event is final. Default is 0."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name(COMMAND_MIGRATE_TO_STREAMS)
+                .about("Copy the events stored under numbered keys to the Redis streams."),
+        )
         .get_matches();
 
-    let result = match matches.subcommand() {
-        (COMMAND_SUBSCRIBE_FOR_COLLECTION_CREATED_EVENT, Some(arg_matches)) => {
-            let contract_address =
-                value_t_or_exit!(arg_matches, COMMAND_ARGUMENT_CONTRACT_ADDRESS, String);
+    let configuration = match ConfigurationLoader::load() {
+        Ok(configuration_) => configuration_,
+        Err(error) => {
+            println!("{}", error.get_report());
+
+            return ();
+        }
+    };
 
-            let contract_address_ = match Address::from_str(contract_address.as_str()) {
-                Ok(contract_address__) => contract_address__,
-                Err(error) => {
-                    println!("{}", &error);
+    if let (COMMAND_MIGRATE_TO_STREAMS, Some(_)) = matches.subcommand() {
+        if let Err(error) = migrate_to_streams(&configuration) {
+            println!("{}", error.get_report());
+        }
 
-                    return ();
-                }
-            };
+        return ();
+    }
 
-            subscribe(ContractEvent::CollectionCreated, contract_address_)
+    let arg_matches = match matches.subcommand() {
+        (COMMAND_SUBSCRIBE | COMMAND_SUBSCRIBE_ALL, Some(arg_matches_)) => arg_matches_,
+        _ => {
+            println!("Invalid command");
+
+            return ();
         }
-        (COMMAND_SUBSCRIBE_FOR_TOKEN_MINTED_EVENT, Some(arg_matches)) => {
-            let contract_address =
-                value_t_or_exit!(arg_matches, COMMAND_ARGUMENT_CONTRACT_ADDRESS, String);
+    };
 
-            let contract_address_ = match Address::from_str(contract_address.as_str()) {
-                Ok(contract_address__) => contract_address__,
-                Err(error) => {
-                    println!("{}", &error);
+    let artifact_path = arg_matches
+        .value_of(COMMAND_ARGUMENT_ARTIFACT)
+        .unwrap_or(configuration.contract.metadata_file_path.as_str());
 
-                    return ();
-                }
-            };
+    let contract_abi = match ContractAbiLoader::load_from_artifact(Path::new(artifact_path)) {
+        Ok(contract_abi_) => contract_abi_,
+        Err(error) => {
+            println!("{}", error.get_report());
 
-            subscribe(ContractEvent::TokenMinted, contract_address_)
+            return ();
         }
-        _ => {
-            println!("Invalid command");
+    };
+
+    let event_name_registry = if arg_matches.is_present(COMMAND_ARGUMENT_EVENT_NAME) {
+        values_t_or_exit!(arg_matches, COMMAND_ARGUMENT_EVENT_NAME, String)
+    } else {
+        contract_abi
+            .events()
+            .map(|event| event.name.clone())
+            .collect::<Vec<String>>()
+    };
+
+    let event_topic_registry =
+        match get_event_topic_registry_from_abi(&contract_abi, event_name_registry.as_slice()) {
+            Ok(event_topic_registry_) => event_topic_registry_,
+            Err(error) => {
+                println!("{}", error.get_report());
+
+                return ();
+            }
+        };
+
+    let contract_address = value_t_or_exit!(arg_matches, COMMAND_ARGUMENT_CONTRACT_ADDRESS, String);
+
+    let contract_address_ = match Address::from_str(contract_address.as_str()) {
+        Ok(contract_address__) => contract_address__,
+        Err(error) => {
+            println!("{}", &error);
+
+            return ();
+        }
+    };
+
+    let from_block = match get_optional_u64(arg_matches, COMMAND_ARGUMENT_FROM_BLOCK) {
+        Ok(from_block_) => from_block_,
+        Err(error) => {
+            println!("{}", error.get_report());
 
             return ();
         }
     };
 
-    if let Err(error) = result {
-        println!("{}", &error);
+    let confirmation_depth =
+        match get_optional_u64(arg_matches, COMMAND_ARGUMENT_CONFIRMATION_DEPTH) {
+            Ok(confirmation_depth_) => confirmation_depth_.unwrap_or(0),
+            Err(error) => {
+                println!("{}", error.get_report());
+
+                return ();
+            }
+        };
+
+    if let Err(error) = subscribe(
+        &configuration,
+        event_topic_registry,
+        contract_address_,
+        from_block,
+        confirmation_depth,
+    ) {
+        println!("{}", error.get_report());
     }
 
     return ();
 }
 
+// The events are resolved by name in the ABI, and every one is stored as the type that has its topic0.
+fn get_event_topic_registry_from_abi<'a>(
+    contract_abi: &'a Contract,
+    event_name_registry: &'a [String],
+) -> Result<Vec<(EventType, H256)>, Error> {
+    let mut event_topic_registry: Vec<(EventType, H256)> = vec![];
+
+    for event_name in event_name_registry.iter() {
+        let topic = EventTopicCreator::create_from_abi(contract_abi, event_name.as_str())?;
+
+        let event_type = match EventType::from_topic(topic)? {
+            Some(event_type_) => event_type_,
+            None => {
+                return Err(Error::Abi {
+                    context: format!("The '{}' event can not be stored", event_name),
+                    source: None,
+                });
+            }
+        };
+
+        if !event_topic_registry.contains(&(event_type, topic)) {
+            event_topic_registry.push((event_type, topic));
+        }
+    }
+
+    return Ok(event_topic_registry);
+}
+
+fn get_optional_u64<'a>(
+    arg_matches: &'a ArgMatches<'a>,
+    name: &'a str,
+) -> Result<Option<u64>, Error> {
+    match arg_matches.value_of(name) {
+        Some(value) => match u64::from_str(value) {
+            Ok(value_) => {
+                return Ok(Some(value_));
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!("Invalid '{}'", name),
+                    source: Some(Box::from(error)),
+                });
+            }
+        },
+        None => {
+            return Ok(None);
+        }
+    }
+}
+
 #[tokio::main]
-async fn subscribe(
-    contract_event: ContractEvent,
+async fn subscribe<'a>(
+    configuration: &'a Configuration,
+    event_topic_registry: Vec<(EventType, H256)>,
     contract_address: Address,
-) -> Result<(), Box<dyn Error + 'static>> {
-    let connection_pool = ConnectionPoolCreator::create(2).await?;
+    from_block: Option<u64>,
+    confirmation_depth: u64,
+) -> Result<(), Error> {
+    match configuration.storage.backend {
+        StorageBackend::Redis => {
+            let connection_pool =
+                ConnectionPoolCreator::create(configuration.redis.url.as_str(), 3).await?;
+
+            let event_store = RedisEventStore::new(
+                connection_pool,
+                configuration.redis.url.clone(),
+                configuration.storage.mode,
+            );
+
+            return run_subscription(
+                &event_store,
+                configuration.ethereum.websocket_url.as_str(),
+                event_topic_registry,
+                contract_address,
+                from_block,
+                confirmation_depth,
+            )
+            .await;
+        }
+        StorageBackend::Sql => {
+            let connection_pool =
+                SqlConnectionPoolCreator::create(configuration.storage.sql_url.as_str(), 3).await?;
 
-    let web3 = Web3ConnectionCreator::create_websocket_connection().await?;
+            let event_store = SqlEventStore::new(connection_pool);
 
-    match contract_event {
-        ContractEvent::CollectionCreated => {
-            let subscription_stream = ContractSubscriptionCreator::create(
-                &web3,
+            return run_subscription(
+                &event_store,
+                configuration.ethereum.websocket_url.as_str(),
+                event_topic_registry,
                 contract_address,
-                CONTRACT_EVENT_COLLECTION_CREATED_HASH,
+                from_block,
+                confirmation_depth,
             )
+            .await;
+        }
+    }
+}
+
+async fn run_subscription<'a, S>(
+    event_store: &'a S,
+    websocket_url: &'a str,
+    event_topic_registry: Vec<(EventType, H256)>,
+    contract_address: Address,
+    from_block: Option<u64>,
+    confirmation_depth: u64,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    // The collections are followed by the process that listens for their creation.
+    let is_collection_followed = event_topic_registry
+        .iter()
+        .any(|(event_type, _)| *event_type == EventType::CollectionCreated);
+
+    let mut subscription = create_subscription(
+        event_topic_registry,
+        contract_address,
+        vec![contract_address],
+        from_block,
+        event_store,
+    )
+    .await?;
+
+    if !is_collection_followed {
+        return run(
+            &mut subscription,
+            &mut None,
+            websocket_url,
+            event_store,
+            confirmation_depth,
+        )
+        .await;
+    }
+
+    let (collection_sender, collection_receiver) = mpsc::unbounded_channel::<FollowedCollection>();
+
+    subscription.collection_sender = Some(collection_sender);
+
+    // The collections are created from the embedded artifact.
+    let mut collection_event_topic_registry: Vec<(EventType, H256)> = vec![];
+
+    for event_type in [
+        EventType::Transfer,
+        EventType::Approval,
+        EventType::ApprovalForAll,
+    ]
+    .into_iter()
+    {
+        collection_event_topic_registry.push((event_type, event_type.get_topic()?));
+    }
+
+    let mut collection_subscription = create_subscription(
+        collection_event_topic_registry,
+        contract_address,
+        vec![],
+        from_block,
+        event_store,
+    )
+    .await?;
+
+    // Without checkpoints of its own, the collection subscription starts where the one of the contract does.
+    // The collections created after that are backfilled from their creation when they are followed.
+    if collection_subscription.resume_from_block.is_none() {
+        collection_subscription.resume_from_block = subscription.resume_from_block;
+    }
+
+    collection_subscription.collection_receiver = Some(collection_receiver);
+
+    return run(
+        &mut subscription,
+        &mut Some(collection_subscription),
+        websocket_url,
+        event_store,
+        confirmation_depth,
+    )
+    .await;
+}
+
+async fn create_subscription<'a, S>(
+    event_topic_registry: Vec<(EventType, H256)>,
+    contract_address: Address,
+    contract_address_registry: Vec<Address>,
+    from_block: Option<u64>,
+    event_store: &'a S,
+) -> Result<Subscription, Error>
+where
+    S: EventStore,
+{
+    let mut event_subscription_registry: Vec<EventSubscription> = vec![];
+
+    for (event_type, topic) in event_topic_registry.into_iter() {
+        let checkpoint = event_store
+            .get_checkpoint(contract_address, event_type)
             .await?;
 
-            subscription_stream
-                .for_each(|log| async {
-                    match log {
-                        Ok(log_) => {
-                            if let Err(error) = push(
-                                &log_,
-                                &connection_pool,
-                                StorageKeyManager::EVENT_COLLECTION_CREATED_QUANTITY,
-                                StorageKeyManager::EVENT_COLLECTION_CREATED_PART,
-                            )
-                            .await
-                            {
-                                panic!("{}", error);
-                            }
-                        }
-                        Err(error) => {
-                            panic!("{}", error);
-                        }
-                    }
-                })
-                .await;
+        event_subscription_registry.push(EventSubscription {
+            event_type,
+            topic,
+            checkpoint,
+        });
+    }
+
+    let mut subscription = Subscription {
+        contract_address,
+        contract_address_registry,
+        event_subscription_registry,
+        start_block: from_block,
+        resume_from_block: None,
+        collection_sender: None,
+        collection_receiver: None,
+    };
+
+    subscription.resume_from_block = match from_block {
+        Some(from_block_) => Some(from_block_),
+        None => get_earliest_checkpoint_block(&subscription),
+    };
+
+    return Ok(subscription);
+}
+
+// Both subscriptions share the connection, so they are created and recovered together.
+async fn run<'a, S>(
+    subscription: &'a mut Subscription,
+    collection_subscription: &'a mut Option<Subscription>,
+    websocket_url: &'a str,
+    event_store: &'a S,
+    confirmation_depth: u64,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    let mut reconnection_backoff = ReconnectionBackoff::new();
+
+    loop {
+        let web3 = Web3ConnectionCreator::create_websocket_connection_supervised(
+            websocket_url,
+            &mut reconnection_backoff,
+            &log_connection_state,
+        )
+        .await;
+
+        let reason = match listen(
+            &web3,
+            subscription,
+            collection_subscription,
+            event_store,
+            confirmation_depth,
+            &mut reconnection_backoff,
+        )
+        .await
+        {
+            Ok(_) => "The subscription stream is closed.".to_string(),
+            Err(error) => {
+                // Only a retryable failure, like a transport one, is worth reconnecting for.
+                if !error.is_retryable() {
+                    return Err(error);
+                }
+
+                error.get_report()
+            }
+        };
+
+        log_connection_state(&ConnectionState::Disconnected { reason });
+
+        update_resume_from_block(subscription);
+
+        if let Some(ref mut collection_subscription_) = *collection_subscription {
+            update_resume_from_block(collection_subscription_);
         }
-        ContractEvent::TokenMinted => {
-            let subscription_stream = ContractSubscriptionCreator::create(
-                &web3,
-                contract_address,
-                CONTRACT_EVENT_TOKEN_MINTED_HASH,
+
+        reconnection_backoff.wait(&log_connection_state).await;
+    }
+}
+
+// The gap is backfilled from the checkpoints. Without them, nothing is known to be missed.
+fn update_resume_from_block<'a>(subscription: &'a mut Subscription) -> () {
+    if let Some(checkpoint_block) = get_earliest_checkpoint_block(subscription) {
+        subscription.resume_from_block = Some(checkpoint_block);
+    }
+
+    return ();
+}
+
+async fn listen<'a, S>(
+    web3: &'a Web3<WebSocket>,
+    subscription: &'a mut Subscription,
+    collection_subscription: &'a mut Option<Subscription>,
+    event_store: &'a S,
+    confirmation_depth: u64,
+    reconnection_backoff: &'a mut ReconnectionBackoff,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    // Both filters are subscribed for over the connection before the backfill, so that no block falls between them.
+    let mut log_stream = create_log_stream(web3, subscription).await?;
+
+    let mut collection_log_stream = match *collection_subscription {
+        Some(ref mut collection_subscription_) => {
+            // The followed collections are rebuilt from the registry of the contract.
+            let collection_registry =
+                CollectionRegistryReader::read(web3, collection_subscription_.contract_address)
+                    .await?;
+
+            for collection in collection_registry.into_iter() {
+                if !collection_subscription_
+                    .contract_address_registry
+                    .contains(&collection)
+                {
+                    collection_subscription_
+                        .contract_address_registry
+                        .push(collection);
+                }
+            }
+
+            create_log_stream(web3, collection_subscription_).await?
+        }
+        None => stream::pending().boxed(),
+    };
+
+    let mut block_header_stream = if confirmation_depth > 0 {
+        BlockHeaderSubscriptionCreator::create(web3).await?.boxed()
+    } else {
+        stream::pending().boxed()
+    };
+
+    reconnection_backoff.reset();
+
+    log_connection_state(&ConnectionState::Subscribed);
+
+    let mut head_block = get_head_block(web3).await?;
+
+    if let Some(resume_from_block) = subscription.resume_from_block {
+        backfill(
+            web3,
+            subscription,
+            resume_from_block,
+            head_block,
+            confirmation_depth,
+            event_store,
+            true,
+        )
+        .await?;
+    }
+
+    confirm(subscription, event_store, head_block, confirmation_depth).await?;
+
+    // The logs up to the head block are read now, and the later ones are received by the stream.
+    subscription.start_block = Some(head_block);
+
+    if let Some(ref mut collection_subscription_) = *collection_subscription {
+        if let Some(resume_from_block) = collection_subscription_.resume_from_block {
+            backfill(
+                web3,
+                collection_subscription_,
+                resume_from_block,
+                head_block,
+                confirmation_depth,
+                event_store,
+                true,
             )
             .await?;
+        }
+
+        confirm(
+            collection_subscription_,
+            event_store,
+            head_block,
+            confirmation_depth,
+        )
+        .await?;
+
+        collection_subscription_.start_block = Some(head_block);
+    }
+
+    loop {
+        tokio::select! {
+            log = log_stream.next() => {
+                let log_ = match log {
+                    Some(Ok(log__)) => log__,
+                    Some(Err(error)) => {
+                        return Err(Error::Transport { context: "The log subscription failed".to_string(), source: error });
+                    }
+                    None => {
+                        return Ok(());
+                    }
+                };
+
+                process(
+                    subscription,
+                    &log_,
+                    head_block,
+                    confirmation_depth,
+                    event_store,
+                    true,
+                )
+                .await?;
+            }
+            collection_log = collection_log_stream.next() => {
+                let collection_log_ = match collection_log {
+                    Some(Ok(collection_log__)) => collection_log__,
+                    Some(Err(error)) => {
+                        return Err(Error::Transport { context: "The collection log subscription failed".to_string(), source: error });
+                    }
+                    None => {
+                        return Ok(());
+                    }
+                };
+
+                if let Some(ref mut collection_subscription_) = *collection_subscription {
+                    process(
+                        collection_subscription_,
+                        &collection_log_,
+                        head_block,
+                        confirmation_depth,
+                        event_store,
+                        true,
+                    )
+                    .await?;
+                }
+            }
+            block_header = block_header_stream.next() => {
+                let block_header_ = match block_header {
+                    Some(Ok(block_header__)) => block_header__,
+                    Some(Err(error)) => {
+                        return Err(Error::Transport { context: "The block header subscription failed".to_string(), source: error });
+                    }
+                    None => {
+                        return Ok(());
+                    }
+                };
+
+                if let Some(block_number) = block_header_.number {
+                    head_block = cmp::max(head_block, block_number.as_u64());
+
+                    confirm(subscription, event_store, head_block, confirmation_depth).await?;
+
+                    if let Some(ref collection_subscription_) = *collection_subscription {
+                        confirm(collection_subscription_, event_store, head_block, confirmation_depth).await?;
+                    }
+                }
+            }
+            followed_collection = receive_followed_collection(collection_subscription) => {
+                let collection_subscription_ = match *collection_subscription {
+                    Some(ref mut collection_subscription__) => collection_subscription__,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let followed_collection_ = match followed_collection {
+                    Some(followed_collection__) => followed_collection__,
+                    None => {
+                        collection_subscription_.collection_receiver = None;
+
+                        continue;
+                    }
+                };
+
+                if collection_subscription_.contract_address_registry.contains(&followed_collection_.address) {
+                    continue;
+                }
+
+                collection_subscription_.contract_address_registry.push(followed_collection_.address);
+
+                // The new subscription is created before the old one is dropped.
+                collection_log_stream = create_log_stream(web3, collection_subscription_).await?;
 
-            subscription_stream
-                .for_each(|log| async {
-                    match log {
-                        Ok(log_) => {
-                            if let Err(error) = push(
-                                &log_,
-                                &connection_pool,
-                                StorageKeyManager::EVENT_TOKEN_MINTED_QUANTITY,
-                                StorageKeyManager::EVENT_TOKEN_MINTED_PART,
-                            )
-                            .await
-                            {
-                                panic!("{}", error);
-                            }
-                        }
-                        Err(error) => {
-                            panic!("{}", error);
-                        }
+                head_block = cmp::max(head_block, get_head_block(web3).await?);
+
+                // The logs of the new collection and the logs emitted while resubscribing are backfilled.
+                // Some of them may be stored already, which the storage tolerates.
+                let from_block = match get_earliest_checkpoint_block(collection_subscription_) {
+                    Some(checkpoint_block) => {
+                        cmp::min(checkpoint_block, followed_collection_.block_number)
                     }
-                })
-                .await;
+                    None => followed_collection_.block_number,
+                };
+
+                backfill(
+                    web3,
+                    collection_subscription_,
+                    from_block,
+                    head_block,
+                    confirmation_depth,
+                    event_store,
+                    false,
+                )
+                .await?;
+            }
+        }
+    }
+}
+
+// A filter without addresses would match the logs of every contract, so nothing is subscribed for then.
+async fn create_log_stream<'a>(
+    web3: &'a Web3<WebSocket>,
+    subscription: &'a Subscription,
+) -> Result<BoxStream<'static, Result<Log, Web3Error>>, Error> {
+    if subscription.contract_address_registry.is_empty() {
+        return Ok(stream::pending().boxed());
+    }
+
+    let subscription_stream = ContractSubscriptionCreator::create(
+        web3,
+        subscription.contract_address_registry.as_slice(),
+        get_event_topic_registry(subscription).as_slice(),
+    )
+    .await?;
+
+    return Ok(subscription_stream.boxed());
+}
+
+async fn receive_followed_collection<'a>(
+    collection_subscription: &'a mut Option<Subscription>,
+) -> Option<FollowedCollection> {
+    match collection_subscription
+        .as_mut()
+        .and_then(|collection_subscription_| collection_subscription_.collection_receiver.as_mut())
+    {
+        Some(collection_receiver) => {
+            return collection_receiver.recv().await;
+        }
+        None => {
+            return future::pending().await;
+        }
+    }
+}
+
+async fn get_head_block<'a>(web3: &'a Web3<WebSocket>) -> Result<u64, Error> {
+    match web3.eth().block_number().await {
+        Ok(head_block) => {
+            return Ok(head_block.as_u64());
+        }
+        Err(error) => {
+            return Err(Error::Transport {
+                context: "Failed to get the head block".to_string(),
+                source: error,
+            });
+        }
+    }
+}
+
+fn log_connection_state<'a>(connection_state: &'a ConnectionState) -> () {
+    println!("{}", connection_state);
+
+    return ();
+}
+
+// The logs of an event without a checkpoint are read from the start block. Without one, the event
+// had no logs while the subscriber listened for it, so the checkpoints of the other events are enough.
+fn get_earliest_checkpoint_block<'a>(subscription: &'a Subscription) -> Option<u64> {
+    let mut earliest_checkpoint_block: Option<u64> = None;
+
+    for event_subscription in subscription.event_subscription_registry.iter() {
+        let block_number = match event_subscription.checkpoint {
+            Some(checkpoint) => checkpoint.block_number,
+            None => match subscription.start_block {
+                Some(start_block) => start_block,
+                None => {
+                    continue;
+                }
+            },
+        };
+
+        earliest_checkpoint_block = match earliest_checkpoint_block {
+            Some(earliest_checkpoint_block_) => {
+                Some(cmp::min(earliest_checkpoint_block_, block_number))
+            }
+            None => Some(block_number),
+        };
+    }
+
+    return earliest_checkpoint_block;
+}
+
+fn get_event_topic_registry<'a>(subscription: &'a Subscription) -> Vec<H256> {
+    return subscription
+        .event_subscription_registry
+        .iter()
+        .map(|event_subscription| event_subscription.topic)
+        .collect::<Vec<H256>>();
+}
+
+async fn backfill<'a, S>(
+    web3: &'a Web3<WebSocket>,
+    subscription: &'a mut Subscription,
+    from_block: u64,
+    to_block: u64,
+    confirmation_depth: u64,
+    event_store: &'a S,
+    is_checkpoint_respected: bool,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    // A filter without addresses would match the logs of every contract.
+    if subscription.contract_address_registry.is_empty() {
+        return Ok(());
+    }
+
+    let event_topic_registry = get_event_topic_registry(subscription);
+
+    let mut page_from_block = from_block;
+
+    while page_from_block <= to_block {
+        let page_to_block = cmp::min(page_from_block + BACKFILL_PAGE_BLOCK_QUANTITY - 1, to_block);
+
+        let log_registry = ContractLogLoader::load(
+            web3,
+            subscription.contract_address_registry.as_slice(),
+            event_topic_registry.as_slice(),
+            page_from_block,
+            page_to_block,
+        )
+        .await?;
+
+        for log in log_registry.iter() {
+            process(
+                subscription,
+                log,
+                to_block,
+                confirmation_depth,
+                event_store,
+                is_checkpoint_respected,
+            )
+            .await?;
         }
+
+        page_from_block = page_to_block + 1;
     }
 
     return Ok(());
 }
 
-async fn push<'a>(
+async fn process<'a, S>(
+    subscription: &'a mut Subscription,
     log: &'a Log,
-    connection_pool: &'a Pool<RedisConnectionManager>,
-    key_event_quantity: &'a str,
-    key_event_part: &'a str,
-) -> Result<(), Box<dyn Error + 'static>> {
-    let log_ = match serde_json::to_string(log) {
-        Ok(data_) => data_,
-        Err(error) => {
-            return Err(Box::from(error));
+    head_block: u64,
+    confirmation_depth: u64,
+    event_store: &'a S,
+    is_checkpoint_respected: bool,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    let contract_address = subscription.contract_address;
+
+    // The event is resolved by the topic0 of the log.
+    let event_subscription = match subscription
+        .event_subscription_registry
+        .iter_mut()
+        .find(|event_subscription| log.topics.first() == Some(&event_subscription.topic))
+    {
+        Some(event_subscription_) => event_subscription_,
+        None => {
+            return Ok(());
         }
     };
 
-    let mut pooled_connection = match connection_pool.get().await {
-        Ok(pooled_connection_) => pooled_connection_,
-        Err(error) => {
-            return Err(Box::from(error));
+    let event_type = event_subscription.event_type;
+
+    let log_checkpoint = EventCheckpoint::from_log(log)?;
+
+    if log.removed == Some(true) {
+        if event_type == EventType::Transfer {
+            retract_ownership(log, &log_checkpoint, event_store).await?;
         }
-    };
 
-    let connection = &mut *pooled_connection;
+        return retract(
+            event_type,
+            contract_address,
+            log,
+            &log_checkpoint,
+            event_store,
+            &mut event_subscription.checkpoint,
+        )
+        .await;
+    }
 
-    let row_quantity = match connection
-        .incr::<'_, &'_ str, i64, i64>(key_event_quantity, 1)
-        .await
+    if let Some(checkpoint) = event_subscription.checkpoint {
+        if is_checkpoint_respected && log_checkpoint <= checkpoint {
+            return Ok(());
+        }
+    }
+
+    let event_status = if confirmation_depth == 0
+        || log_checkpoint.block_number + confirmation_depth <= head_block
     {
-        Ok(row_quantity_) => row_quantity_,
+        EventStatus::Final
+    } else {
+        EventStatus::Pending
+    };
+
+    // The ownership is indexed first, because the pushed log is not processed again.
+    if event_type == EventType::Transfer {
+        index_ownership(log, &log_checkpoint, event_store).await?;
+    }
+
+    push(
+        event_type,
+        contract_address,
+        log,
+        &log_checkpoint,
+        event_status,
+        event_store,
+    )
+    .await?;
+
+    event_subscription.checkpoint = match event_subscription.checkpoint {
+        Some(checkpoint) => Some(cmp::max(checkpoint, log_checkpoint)),
+        None => Some(log_checkpoint),
+    };
+
+    if event_type == EventType::CollectionCreated {
+        if let Some(ref collection_sender) = subscription.collection_sender {
+            let collection_created_event = ContractEventDecoder::decode_collection_created(log)?;
+
+            // The collection subscription is gone only if it has failed, which is reported by itself.
+            let _ = collection_sender.send(FollowedCollection {
+                address: collection_created_event.collection,
+                block_number: collection_created_event.block_number,
+            });
+        }
+    }
+
+    return Ok(());
+}
+
+async fn push<'a, S>(
+    event_type: EventType,
+    contract_address: Address,
+    log: &'a Log,
+    log_checkpoint: &'a EventCheckpoint,
+    event_status: EventStatus,
+    event_store: &'a S,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    let data = match event_type {
+        EventType::CollectionCreated => serde_json::to_string(&EventRecord {
+            status: event_status,
+            event: ContractEventDecoder::decode_collection_created(log)?,
+        }),
+        EventType::TokenMinted => serde_json::to_string(&EventRecord {
+            status: event_status,
+            event: ContractEventDecoder::decode_token_minted(log)?,
+        }),
+        EventType::Transfer => serde_json::to_string(&EventRecord {
+            status: event_status,
+            event: ContractEventDecoder::decode_transfer(log)?,
+        }),
+        EventType::Approval => serde_json::to_string(&EventRecord {
+            status: event_status,
+            event: ContractEventDecoder::decode_approval(log)?,
+        }),
+        EventType::ApprovalForAll => serde_json::to_string(&EventRecord {
+            status: event_status,
+            event: ContractEventDecoder::decode_approval_for_all(log)?,
+        }),
+    };
+
+    let data_ = match data {
+        Ok(data__) => data__,
         Err(error) => {
-            return Err(Box::from(error));
+            return Err(Error::Serialization {
+                context: "Failed to serialize the event record".to_string(),
+                source: error,
+            });
         }
     };
 
-    let key_event = StorageKeyManager::create_key_event(key_event_part, row_quantity);
+    let is_appended = event_store
+        .append(
+            contract_address,
+            event_type,
+            *log_checkpoint,
+            ContractEventDecoder::get_transaction_hash(log)?,
+            event_status,
+            data_,
+        )
+        .await?;
 
-    if let Err(error) = connection
-        .set::<'_, String, String, ()>(key_event, log_)
-        .await
-    {
-        return Err(Box::from(error));
+    if !is_appended {
+        println!(
+            "The '{}' event at block {} and log index {} is stored already.",
+            event_type.get_event_name(),
+            log_checkpoint.block_number,
+            log_checkpoint.log_index
+        );
+    }
+
+    return Ok(());
+}
+
+async fn retract<'a, S>(
+    event_type: EventType,
+    contract_address: Address,
+    log: &'a Log,
+    log_checkpoint: &'a EventCheckpoint,
+    event_store: &'a S,
+    checkpoint: &'a mut Option<EventCheckpoint>,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    event_store
+        .retract(
+            event_type,
+            *log_checkpoint,
+            ContractEventDecoder::get_transaction_hash(log)?,
+        )
+        .await?;
+
+    // The checkpoint is rolled back, so that the logs replacing the retracted one are not skipped.
+    let checkpoint_ = match *checkpoint {
+        Some(checkpoint__) => checkpoint__,
+        None => {
+            return Ok(());
+        }
+    };
+
+    match log_checkpoint.get_previous() {
+        Some(previous_checkpoint) => {
+            if previous_checkpoint < checkpoint_ {
+                event_store
+                    .set_checkpoint(contract_address, event_type, previous_checkpoint)
+                    .await?;
+
+                *checkpoint = Some(previous_checkpoint);
+            }
+        }
+        None => {
+            event_store
+                .remove_checkpoint(contract_address, event_type)
+                .await?;
+
+            *checkpoint = None;
+        }
+    }
+
+    return Ok(());
+}
+
+async fn index_ownership<'a, S>(
+    log: &'a Log,
+    log_checkpoint: &'a EventCheckpoint,
+    event_store: &'a S,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    let transfer_event = ContractEventDecoder::decode_transfer(log)?;
+
+    return event_store
+        .index_ownership(TokenOwnership {
+            collection: transfer_event.collection,
+            token_id: transfer_event.token_id,
+            owner: transfer_event.to,
+            block_number: log_checkpoint.block_number,
+            log_index: log_checkpoint.log_index,
+        })
+        .await;
+}
+
+async fn retract_ownership<'a, S>(
+    log: &'a Log,
+    log_checkpoint: &'a EventCheckpoint,
+    event_store: &'a S,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    let transfer_event = ContractEventDecoder::decode_transfer(log)?;
+
+    return event_store
+        .retract_ownership(
+            transfer_event.collection,
+            transfer_event.token_id,
+            *log_checkpoint,
+        )
+        .await;
+}
+
+async fn confirm<'a, S>(
+    subscription: &'a Subscription,
+    event_store: &'a S,
+    head_block: u64,
+    confirmation_depth: u64,
+) -> Result<(), Error>
+where
+    S: EventStore,
+{
+    if confirmation_depth == 0 || head_block < confirmation_depth {
+        return Ok(());
+    }
+
+    for event_subscription in subscription.event_subscription_registry.iter() {
+        event_store
+            .confirm(
+                event_subscription.event_type,
+                head_block - confirmation_depth,
+            )
+            .await?;
+    }
+
+    return Ok(());
+}
+
+#[tokio::main]
+async fn migrate_to_streams<'a>(configuration: &'a Configuration) -> Result<(), Error> {
+    let connection_pool =
+        ConnectionPoolCreator::create(configuration.redis.url.as_str(), 1).await?;
+
+    let event_store = RedisEventStore::new(
+        connection_pool,
+        configuration.redis.url.clone(),
+        configuration.storage.mode,
+    );
+
+    for event_migration in event_store.migrate_to_streams().await?.iter() {
+        println!(
+            "'{}': {} of {} events are migrated.",
+            event_migration.event_type.get_name(),
+            event_migration.migrated_quantity,
+            event_migration.quantity
+        );
     }
 
     return Ok(());
 }
 
-enum ContractEvent {
-    CollectionCreated,
-    TokenMinted,
+struct Subscription {
+    // Checkpoints are kept under this address.
+    contract_address: Address,
+    // Logs are listened for from these addresses.
+    contract_address_registry: Vec<Address>,
+    event_subscription_registry: Vec<EventSubscription>,
+    // The logs of the events without a checkpoint are read from this block.
+    start_block: Option<u64>,
+    resume_from_block: Option<u64>,
+    // Receives the collections created by the contract.
+    collection_sender: Option<UnboundedSender<FollowedCollection>>,
+    // Is present if the subscription follows the collections created by the contract.
+    collection_receiver: Option<UnboundedReceiver<FollowedCollection>>,
+}
+
+struct EventSubscription {
+    event_type: EventType,
+    // The topic0 of the event logs.
+    topic: H256,
+    checkpoint: Option<EventCheckpoint>,
+}
+
+struct FollowedCollection {
+    address: Address,
+    block_number: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use infrastructure::web3::types::Bytes;
+    use infrastructure::web3::types::U256;
+    use infrastructure::web3::types::U64;
+    use infrastructure::EventPageStart;
+    use infrastructure::InMemoryEventStore;
+    use infrastructure::TransferEvent;
+
+    fn get_event_topic_registry<'a>(
+        event_type_registry: &'a [EventType],
+    ) -> Vec<(EventType, H256)> {
+        return event_type_registry
+            .iter()
+            .map(|event_type| (*event_type, event_type.get_topic().unwrap()))
+            .collect::<Vec<(EventType, H256)>>();
+    }
+
+    #[test]
+    fn resolve_events_from_artifact() -> () {
+        let contract_abi = ContractAbiLoader::load_from_artifact(Path::new(
+            "./../../../contract/build/contracts/Collection.json",
+        ))
+        .unwrap();
+
+        let event_topic_registry = get_event_topic_registry_from_abi(
+            &contract_abi,
+            &["Transfer".to_string(), "Transfer".to_string()],
+        )
+        .unwrap();
+
+        assert!(event_topic_registry == get_event_topic_registry(&[EventType::Transfer]));
+
+        assert!(
+            get_event_topic_registry_from_abi(&contract_abi, &["TokenMinted".to_string()]).is_err()
+        );
+
+        return ();
+    }
+
+    // The token 7 of the collection is transferred in the log, whose transaction hash is its block number.
+    fn create_transfer_log<'a>(
+        from: Address,
+        to: Address,
+        log_checkpoint: &'a EventCheckpoint,
+    ) -> Log {
+        return Log {
+            address: Address::repeat_byte(1),
+            topics: vec![
+                EventType::Transfer.get_topic().unwrap(),
+                H256::from(from),
+                H256::from(to),
+                H256::from_low_u64_be(7),
+            ],
+            data: Bytes(vec![]),
+            block_hash: None,
+            block_number: Some(U64::from(log_checkpoint.block_number)),
+            transaction_hash: Some(H256::from_low_u64_be(log_checkpoint.block_number)),
+            transaction_index: None,
+            log_index: Some(U256::from(log_checkpoint.log_index)),
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+    }
+
+    async fn get_owner<'a>(event_store: &'a InMemoryEventStore) -> Option<Address> {
+        return event_store
+            .get_token_ownership(Address::repeat_byte(1), U256::from(7))
+            .await
+            .unwrap()
+            .map(|token_ownership| token_ownership.owner);
+    }
+
+    #[tokio::test]
+    async fn process_stores_retracts_and_confirms_logs() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let collection = Address::repeat_byte(1);
+
+        let mut subscription = create_subscription(
+            get_event_topic_registry(&[EventType::Transfer]),
+            collection,
+            vec![collection],
+            None,
+            &event_store,
+        )
+        .await
+        .unwrap();
+
+        let (owner, retracted_owner) = (Address::repeat_byte(4), Address::repeat_byte(5));
+
+        let mint_checkpoint = EventCheckpoint {
+            block_number: 10,
+            log_index: 0,
+        };
+
+        let transfer_checkpoint = EventCheckpoint {
+            block_number: 11,
+            log_index: 0,
+        };
+
+        let mint_log = create_transfer_log(Address::zero(), owner, &mint_checkpoint);
+
+        let mut transfer_log = create_transfer_log(owner, retracted_owner, &transfer_checkpoint);
+
+        // Both logs are pending with the confirmation depth of 2, and the log before the checkpoint is skipped.
+        for log in [&mint_log, &transfer_log, &mint_log] {
+            process(&mut subscription, log, 11, 2, &event_store, true)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(event_store.count(EventType::Transfer).await.unwrap(), 2);
+        assert!(
+            event_store
+                .get_checkpoint(collection, EventType::Transfer)
+                .await
+                .unwrap()
+                == Some(transfer_checkpoint)
+        );
+        assert_eq!(get_owner(&event_store).await, Some(retracted_owner));
+
+        // The log of another event is not processed.
+        let mut approval_log = mint_log.clone();
+        approval_log.topics[0] = EventType::Approval.get_topic().unwrap();
+
+        process(&mut subscription, &approval_log, 11, 2, &event_store, true)
+            .await
+            .unwrap();
+
+        assert_eq!(event_store.count(EventType::Transfer).await.unwrap(), 2);
+
+        // The removed log is retracted, and the checkpoint is rolled back before it.
+        transfer_log.removed = Some(true);
+
+        process(&mut subscription, &transfer_log, 11, 2, &event_store, true)
+            .await
+            .unwrap();
+
+        assert_eq!(event_store.count(EventType::Transfer).await.unwrap(), 1);
+        assert!(
+            subscription.event_subscription_registry[0].checkpoint
+                == transfer_checkpoint.get_previous()
+        );
+        assert_eq!(get_owner(&event_store).await, Some(owner));
+
+        confirm(&subscription, &event_store, 12, 2).await.unwrap();
+
+        let data = event_store
+            .get_page(EventType::Transfer, EventPageStart::Offset(0), 5)
+            .await
+            .unwrap()
+            .remove(0)
+            .data;
+
+        assert!(
+            serde_json::from_str::<EventRecord<TransferEvent>>(data.as_str())
+                .unwrap()
+                .status
+                == EventStatus::Final
+        );
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn retract_ownership_after_reorg() -> () {
+        let mint_checkpoint = EventCheckpoint {
+            block_number: 5,
+            log_index: 0,
+        };
+
+        let retracted_checkpoint = EventCheckpoint {
+            block_number: 10,
+            log_index: 3,
+        };
+
+        // The block 10 is replaced by the one that has another transfer of the token at a lower log index.
+        let replacing_checkpoint = EventCheckpoint {
+            block_number: 10,
+            log_index: 1,
+        };
+
+        let (minter, retracted_owner, owner) = (
+            Address::repeat_byte(4),
+            Address::repeat_byte(5),
+            Address::repeat_byte(6),
+        );
+
+        let mint_log = create_transfer_log(Address::zero(), minter, &mint_checkpoint);
+
+        let retracted_log = create_transfer_log(minter, retracted_owner, &retracted_checkpoint);
+
+        let replacing_log = create_transfer_log(minter, owner, &replacing_checkpoint);
+
+        // The retraction comes before the replacing log, or after it.
+        for is_retracted_first in [true, false] {
+            let event_store = InMemoryEventStore::new();
+
+            index_ownership(&mint_log, &mint_checkpoint, &event_store)
+                .await
+                .unwrap();
+            index_ownership(&retracted_log, &retracted_checkpoint, &event_store)
+                .await
+                .unwrap();
+
+            assert_eq!(get_owner(&event_store).await, Some(retracted_owner));
+
+            if is_retracted_first {
+                retract_ownership(&retracted_log, &retracted_checkpoint, &event_store)
+                    .await
+                    .unwrap();
+
+                assert_eq!(get_owner(&event_store).await, Some(minter));
+
+                index_ownership(&replacing_log, &replacing_checkpoint, &event_store)
+                    .await
+                    .unwrap();
+            } else {
+                index_ownership(&replacing_log, &replacing_checkpoint, &event_store)
+                    .await
+                    .unwrap();
+
+                assert_eq!(get_owner(&event_store).await, Some(retracted_owner));
+
+                retract_ownership(&retracted_log, &retracted_checkpoint, &event_store)
+                    .await
+                    .unwrap();
+            }
+
+            assert_eq!(get_owner(&event_store).await, Some(owner));
+
+            for (address, owned_token_quantity) in [(minter, 0), (retracted_owner, 0), (owner, 1)] {
+                assert_eq!(
+                    event_store
+                        .get_owned_token_registry(address)
+                        .await
+                        .unwrap()
+                        .len(),
+                    owned_token_quantity
+                );
+            }
+
+            // The retraction of the mint removes the ownership once the other transfers are retracted.
+            retract_ownership(&replacing_log, &replacing_checkpoint, &event_store)
+                .await
+                .unwrap();
+            retract_ownership(&mint_log, &mint_checkpoint, &event_store)
+                .await
+                .unwrap();
+
+            assert_eq!(get_owner(&event_store).await, None);
+        }
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn resume_from_checkpoint_of_one_event() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let contract_address = Address::repeat_byte(1);
+
+        event_store
+            .set_checkpoint(
+                contract_address,
+                EventType::CollectionCreated,
+                EventCheckpoint {
+                    block_number: 10,
+                    log_index: 2,
+                },
+            )
+            .await
+            .unwrap();
+
+        // No token is minted yet, so the TokenMinted event has no checkpoint.
+        let mut subscription = create_subscription(
+            get_event_topic_registry(&[EventType::CollectionCreated, EventType::TokenMinted]),
+            contract_address,
+            vec![contract_address],
+            None,
+            &event_store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(subscription.resume_from_block, Some(10));
+
+        // Once a session has read the logs up to its head block, the event without a checkpoint
+        // is read from there, unless the other checkpoints are earlier.
+        subscription.start_block = Some(20);
+
+        update_resume_from_block(&mut subscription);
+
+        assert_eq!(subscription.resume_from_block, Some(10));
+
+        subscription.event_subscription_registry[0].checkpoint = Some(EventCheckpoint {
+            block_number: 30,
+            log_index: 0,
+        });
+
+        update_resume_from_block(&mut subscription);
+
+        assert_eq!(subscription.resume_from_block, Some(20));
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn resume_from_start_block_without_checkpoint() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let contract_address = Address::repeat_byte(1);
+
+        let subscription = create_subscription(
+            get_event_topic_registry(&[EventType::Transfer, EventType::ApprovalForAll]),
+            contract_address,
+            vec![],
+            None,
+            &event_store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(subscription.resume_from_block, None);
+
+        event_store
+            .set_checkpoint(
+                contract_address,
+                EventType::Transfer,
+                EventCheckpoint {
+                    block_number: 40,
+                    log_index: 0,
+                },
+            )
+            .await
+            .unwrap();
+
+        let subscription_ = create_subscription(
+            get_event_topic_registry(&[EventType::Transfer, EventType::ApprovalForAll]),
+            contract_address,
+            vec![],
+            Some(5),
+            &event_store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(subscription_.resume_from_block, Some(5));
+        assert_eq!(get_earliest_checkpoint_block(&subscription_), Some(5));
+
+        return ();
+    }
 }
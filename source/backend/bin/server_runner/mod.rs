@@ -70,8 +70,10 @@
     clippy::zero_sized_map_values
 )]
 
-use infrastructure::bb8::Pool;
-use infrastructure::bb8_redis::RedisConnectionManager;
+use infrastructure::hyper::body::Bytes;
+use infrastructure::hyper::body::Sender;
+use infrastructure::hyper::server::conn::AddrIncoming;
+use infrastructure::hyper::server::Builder;
 use infrastructure::hyper::service::make_service_fn;
 use infrastructure::hyper::service::service_fn;
 use infrastructure::hyper::Body;
@@ -80,58 +82,174 @@ use infrastructure::hyper::Method;
 use infrastructure::hyper::Request;
 use infrastructure::hyper::Response;
 use infrastructure::hyper::Server;
-use infrastructure::redis::AsyncCommands;
+use infrastructure::serde::de::DeserializeOwned;
+use infrastructure::serde::Serialize;
+use infrastructure::serde_json;
+use infrastructure::serde_json::json;
+use infrastructure::serde_json::Value;
+use infrastructure::web3::futures::stream::BoxStream;
+use infrastructure::web3::futures::StreamExt;
+use infrastructure::web3::transports::Http;
+use infrastructure::web3::types::Address;
+use infrastructure::web3::types::U256;
+use infrastructure::web3::Web3;
+use infrastructure::ApprovalEvent;
+use infrastructure::ApprovalForAllEvent;
+use infrastructure::CollectionCreatedEvent;
+use infrastructure::CollectionMetadataReader;
+use infrastructure::CollectionPage;
+use infrastructure::CollectionRegistryReader;
+use infrastructure::CollectionSummary;
+use infrastructure::ConfigurationLoader;
 use infrastructure::ConnectionPoolCreator;
+use infrastructure::Error;
+use infrastructure::EventChange;
+use infrastructure::EventIndex;
+use infrastructure::EventNotification;
+use infrastructure::EventPage;
+use infrastructure::EventPageStart;
+use infrastructure::EventRecord;
+use infrastructure::EventStore;
+use infrastructure::EventType;
+use infrastructure::RedisEventStore;
 use infrastructure::ResponseCreator;
-use infrastructure::StorageKeyManager;
+use infrastructure::SqlConnectionPoolCreator;
+use infrastructure::SqlEventStore;
+use infrastructure::StorageBackend;
+use infrastructure::TokenMintedEvent;
+use infrastructure::TransferEvent;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::convert::From;
-use std::error::Error;
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fmt::Formatter;
+use std::convert::TryFrom;
+use std::error::Error as StdError;
 use std::net::ToSocketAddrs;
 use std::str::FromStr;
+use std::time::Duration;
 use tokio::signal;
+use tokio::time;
 
-const SERVER_SOCKET_ADDRESS: &'static str = "0.0.0.0:80";
+const EVENT_PAGE_DEFAULT_LIMIT: i64 = 20;
+const EVENT_PAGE_MAXIMUM_LIMIT: i64 = 100;
+const EVENT_STREAM_KEEP_ALIVE_INTERVAL_SECONDS: u64 = 15;
+
+// The collections are verified with the registry of the CollectionAggregator, if its address is configured.
+#[derive(Clone)]
+struct CollectionRegistry {
+    web3: Web3<Http>,
+    collection_aggregator_address: Address,
+}
 
 fn main() -> () {
     if let Err(error) = run_server() {
-        println!("{}", &error);
+        match error.downcast_ref::<Error>() {
+            Some(error_) => {
+                println!("{}", error_.get_report());
+            }
+            None => {
+                println!("{}", &error);
+            }
+        }
     }
 
     return ();
 }
 
 #[tokio::main]
-async fn run_server() -> Result<(), Box<dyn Error + 'static>> {
-    let mut server_socket_address_registry = match SERVER_SOCKET_ADDRESS.to_socket_addrs() {
-        Ok(server_socket_address_registry_) => server_socket_address_registry_,
-        Err(error) => {
-            return Err(Box::from(error));
-        }
-    };
+async fn run_server() -> Result<(), Box<dyn StdError + 'static>> {
+    let configuration = ConfigurationLoader::load()?;
+
+    let mut server_socket_address_registry =
+        match configuration.server.socket_address.to_socket_addrs() {
+            Ok(server_socket_address_registry_) => server_socket_address_registry_,
+            Err(error) => {
+                return Err(Box::from(error));
+            }
+        };
 
     let server_socket_address = match server_socket_address_registry.next() {
         Some(server_socket_address_) => server_socket_address_,
         None => {
-            return Err(Box::from(LogicError));
+            return Err(Box::from(Error::Config {
+                context: format!(
+                    "The '{}' server socket address resolves to no address",
+                    configuration.server.socket_address
+                ),
+                source: None,
+            }));
         }
     };
 
     let builder = Server::bind(&server_socket_address);
 
-    let connection_pool = ConnectionPoolCreator::create(5).await?;
+    let collection_registry = match configuration.contract.get_address() {
+        Some(collection_aggregator_address) => {
+            let transport = match Http::new(configuration.ethereum.http_url.as_str()) {
+                Ok(transport_) => transport_,
+                Err(error) => {
+                    return Err(Box::from(error));
+                }
+            };
+
+            Some(CollectionRegistry {
+                web3: Web3::new(transport),
+                collection_aggregator_address,
+            })
+        }
+        None => None,
+    };
+
+    match configuration.storage.backend {
+        StorageBackend::Redis => {
+            let connection_pool =
+                ConnectionPoolCreator::create(configuration.redis.url.as_str(), 5).await?;
 
+            let event_store = RedisEventStore::new(
+                connection_pool,
+                configuration.redis.url.clone(),
+                configuration.storage.mode,
+            );
+
+            return serve(builder, event_store, collection_registry).await;
+        }
+        StorageBackend::Sql => {
+            let connection_pool =
+                SqlConnectionPoolCreator::create(configuration.storage.sql_url.as_str(), 5).await?;
+
+            return serve(
+                builder,
+                SqlEventStore::new(connection_pool),
+                collection_registry,
+            )
+            .await;
+        }
+    }
+}
+
+async fn serve<S>(
+    builder: Builder<AddrIncoming>,
+    event_store: S,
+    collection_registry: Option<CollectionRegistry>,
+) -> Result<(), Box<dyn StdError + 'static>>
+where
+    S: EventStore,
+{
     let service = make_service_fn(move |_| {
-        let connection_pool_ = connection_pool.clone();
+        let event_store_ = event_store.clone();
+
+        let collection_registry_ = collection_registry.clone();
 
         async move {
             return Ok::<_, HyperError>(service_fn(move |requset| {
-                let connection_pool__ = connection_pool_.clone();
+                let event_store__ = event_store_.clone();
+
+                let collection_registry__ = collection_registry_.clone();
 
                 return async move {
-                    return Ok::<_, HyperError>(resolve(requset, &connection_pool__).await);
+                    return Ok::<_, HyperError>(
+                        resolve(requset, &event_store__, &collection_registry__).await,
+                    );
                 };
             }));
         }
@@ -148,29 +266,121 @@ async fn run_server() -> Result<(), Box<dyn Error + 'static>> {
     return Ok(());
 }
 
-async fn resolve<'a>(
+async fn resolve<'a, S>(
     request: Request<Body>,
-    connection_pool: &'a Pool<RedisConnectionManager>,
-) -> Response<Body> {
-    let route = request.uri().path();
+    event_store: &'a S,
+    collection_registry: &'a Option<CollectionRegistry>,
+) -> Response<Body>
+where
+    S: EventStore,
+{
+    let route = request.uri().path().to_string();
+
+    let route_segment_registry = route.split('/').collect::<Vec<&'_ str>>();
 
     let method = request.method();
 
-    let response = match (route, method) {
-        ("/event/collection_created/quantity", &Method::GET) => {
-            get_event_collection_created_quantity(connection_pool).await
+    let response = match (route_segment_registry.as_slice(), method) {
+        (["", "event", "stream"], &Method::GET) => get_event_stream(&request, event_store).await,
+        (["", "event", "collection_created", "quantity"], &Method::GET) => {
+            get_event_collection_created_quantity(event_store).await
         }
-        ("/event/collection_created", &Method::GET) => {
-            get_event_collection_created_by_index(request, connection_pool).await
+        (["", "event", "collection_created"], &Method::GET) => {
+            if is_parameter_in_query(&request, "cursor") || is_parameter_in_query(&request, "index")
+            {
+                get_event_collection_created(request, event_store).await
+            } else {
+                get_event_page::<CollectionCreatedEvent, S>(
+                    &request,
+                    EventType::CollectionCreated,
+                    None,
+                    event_store,
+                )
+                .await
+            }
+        }
+        (["", "event", "token_minted", "quantity"], &Method::GET) => {
+            get_event_token_minted_quantity(event_store).await
+        }
+        (["", "event", "token_minted"], &Method::GET) => {
+            if is_parameter_in_query(&request, "cursor") || is_parameter_in_query(&request, "index")
+            {
+                get_event_token_minted(request, event_store).await
+            } else {
+                get_event_page::<TokenMintedEvent, S>(
+                    &request,
+                    EventType::TokenMinted,
+                    None,
+                    event_store,
+                )
+                .await
+            }
+        }
+        (["", "event", "transfer"], &Method::GET) => {
+            get_event_page::<TransferEvent, S>(&request, EventType::Transfer, None, event_store)
+                .await
+        }
+        (["", "event", "approval"], &Method::GET) => {
+            get_event_page::<ApprovalEvent, S>(&request, EventType::Approval, None, event_store)
+                .await
+        }
+        (["", "event", "approval_for_all"], &Method::GET) => {
+            get_event_page::<ApprovalForAllEvent, S>(
+                &request,
+                EventType::ApprovalForAll,
+                None,
+                event_store,
+            )
+            .await
         }
-        ("/event/token_minted/quantity", &Method::GET) => {
-            get_event_token_minted_quantity(connection_pool).await
+        (["", "collections"], &Method::GET) => {
+            get_collection_page(&request, event_store, collection_registry).await
         }
-        ("/event/token_minted", &Method::GET) => {
-            get_event_token_minted_by_index(request, connection_pool).await
+        (["", "collection", collection], &Method::GET) => {
+            get_collection(collection, event_store, collection_registry).await
+        }
+        (["", "collection", collection, "token", token_id], &Method::GET) => {
+            get_token_ownership(collection, token_id, event_store).await
+        }
+        (["", "owner", owner, "tokens"], &Method::GET) => {
+            get_owned_token_registry(owner, event_store).await
+        }
+        (["", "collection", collection, "mints"], &Method::GET) => {
+            match Address::from_str(collection) {
+                Ok(collection_) => {
+                    get_event_page::<TokenMintedEvent, S>(
+                        &request,
+                        EventType::TokenMinted,
+                        Some(EventIndex::Collection(collection_)),
+                        event_store,
+                    )
+                    .await
+                }
+                Err(_) => ResponseCreator::create_bad_request(
+                    "The collection must be an address.",
+                    Some(json!({ "collection": collection })),
+                ),
+            }
+        }
+        (["", "recipient", recipient, "mints"], &Method::GET) => {
+            match Address::from_str(recipient) {
+                Ok(recipient_) => {
+                    get_event_page::<TokenMintedEvent, S>(
+                        &request,
+                        EventType::TokenMinted,
+                        Some(EventIndex::Recipient(recipient_)),
+                        event_store,
+                    )
+                    .await
+                }
+                Err(_) => ResponseCreator::create_bad_request(
+                    "The recipient must be an address.",
+                    Some(json!({ "recipient": recipient })),
+                ),
+            }
         }
         _ => {
-            return ResponseCreator::create_not_found();
+            return ResponseCreator::create_not_found("The route is not found.", None);
         }
     };
 
@@ -200,191 +410,1457 @@ where
     return None;
 }
 
-async fn get_event_collection_created_quantity<'a>(
-    connection_pool: &'a Pool<RedisConnectionManager>,
-) -> Response<Body> {
-    let mut pooled_connection = match connection_pool.get().await {
-        Ok(pooled_connection_) => pooled_connection_,
-        Err(_) => {
-            return ResponseCreator::create_internal_server_error();
+fn is_parameter_in_query<'a>(request: &'a Request<Body>, key: &'a str) -> bool {
+    match request.uri().query() {
+        Some(query) => {
+            return form_urlencoded::parse(query.as_bytes()).any(|(key_, _)| key_ == key);
+        }
+        None => {
+            return false;
+        }
+    }
+}
+
+// The page starts after the zero-based offset or the cursor. The error is the message of the bad request.
+fn get_page_parameters<'a>(request: &'a Request<Body>) -> Result<(EventPageStart, i64), String> {
+    let query = request.uri().query().unwrap_or("");
+
+    let limit = if is_parameter_in_query(request, "limit") {
+        match get_parameter_from_query::<i64>(query.as_bytes(), "limit") {
+            Some(limit_) if (1..=EVENT_PAGE_MAXIMUM_LIMIT).contains(&limit_) => limit_,
+            _ => {
+                return Err(format!(
+                    "The 'limit' parameter must be from 1 to {}.",
+                    EVENT_PAGE_MAXIMUM_LIMIT
+                ));
+            }
         }
+    } else {
+        EVENT_PAGE_DEFAULT_LIMIT
     };
 
-    let connection = &mut *pooled_connection;
+    let is_offset_requested = is_parameter_in_query(request, "offset");
 
-    let quantity = match connection
-        .get::<'_, &'_ str, Option<String>>(StorageKeyManager::EVENT_COLLECTION_CREATED_QUANTITY)
-        .await
-    {
+    let is_cursor_requested = is_parameter_in_query(request, "after");
+
+    let page_start = match (is_offset_requested, is_cursor_requested) {
+        (false, false) => EventPageStart::Offset(0),
+        (true, false) => match get_parameter_from_query::<i64>(query.as_bytes(), "offset") {
+            Some(offset) if offset >= 0 => EventPageStart::Offset(offset),
+            _ => {
+                return Err("The 'offset' parameter must be a non-negative integer.".to_string());
+            }
+        },
+        (false, true) => match get_parameter_from_query::<String>(query.as_bytes(), "after") {
+            Some(cursor) => EventPageStart::After(cursor),
+            None => {
+                return Err("The 'after' parameter must be a cursor.".to_string());
+            }
+        },
+        (true, true) => {
+            return Err(
+                "The 'offset' and 'after' parameters can not be used together.".to_string(),
+            );
+        }
+    };
+
+    return Ok((page_start, limit));
+}
+
+// The registry of the contract is addressed by the position in it, which is its cursor too.
+fn get_registry_index<'a>(page_start: &'a EventPageStart) -> Option<i64> {
+    match *page_start {
+        EventPageStart::Offset(offset) => {
+            return Some(offset);
+        }
+        EventPageStart::After(ref cursor) => {
+            return i64::from_str(cursor.as_str())
+                .ok()
+                .filter(|offset| *offset >= 0);
+        }
+    }
+}
+
+// The page is addressed by the zero-based offset or by the cursor, which is the one of the last event
+// of the previous page. One more event is read to know if there is a next page. The cursor of
+// an indexed event is its checkpoint, if the index is given.
+async fn get_event_page<'a, T, S>(
+    request: &'a Request<Body>,
+    event_type: EventType,
+    event_index: Option<EventIndex>,
+    event_store: &'a S,
+) -> Response<Body>
+where
+    T: Serialize + DeserializeOwned,
+    S: EventStore,
+{
+    let (page_start, limit) = match get_page_parameters(request) {
+        Ok(page_parameters) => page_parameters,
+        Err(message) => {
+            return ResponseCreator::create_bad_request(message.as_str(), None);
+        }
+    };
+
+    let quantity = match event_index {
+        Some(event_index_) => event_store.count_indexed(event_type, event_index_).await,
+        None => event_store.count(event_type).await,
+    };
+
+    let total = match quantity {
         Ok(quantity_) => quantity_,
         Err(_) => {
             return ResponseCreator::create_internal_server_error();
         }
     };
 
-    match quantity {
-        Some(quantity_) => {
-            return ResponseCreator::create_ok(Some(quantity_));
+    let page_result = match event_index {
+        Some(event_index_) => {
+            event_store
+                .get_indexed_page(event_type, event_index_, page_start, limit + 1)
+                .await
         }
         None => {
-            return ResponseCreator::create_ok(Some(
-                "There are no events of CreateCollection type yet.".to_string(),
-            ));
+            event_store
+                .get_page(event_type, page_start, limit + 1)
+                .await
+        }
+    };
+
+    let mut event_entry_registry = match page_result {
+        Ok(event_entry_registry_) => event_entry_registry_,
+        Err(Error::NotFound { .. }) => {
+            return ResponseCreator::create_bad_request(
+                "The 'after' parameter must be a cursor of the page.",
+                None,
+            );
+        }
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
+    };
+
+    let next_cursor = if event_entry_registry.len() as i64 > limit {
+        event_entry_registry.truncate(limit as usize);
+
+        event_entry_registry
+            .last()
+            .map(|event_entry| event_entry.cursor.clone())
+    } else {
+        None
+    };
+
+    let data_registry = event_entry_registry
+        .into_iter()
+        .map(|event_entry| event_entry.data)
+        .collect::<Vec<String>>();
+
+    let mut event_record_registry: Vec<EventRecord<T>> = vec![];
+
+    for data in data_registry.into_iter() {
+        match serde_json::from_str::<EventRecord<T>>(data.as_str()) {
+            Ok(event_record) => {
+                event_record_registry.push(event_record);
+            }
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
         }
     }
-}
 
-async fn get_event_collection_created_by_index<'a>(
-    request: Request<Body>,
-    connection_pool: &'a Pool<RedisConnectionManager>,
-) -> Response<Body> {
-    let query = match request.uri().query() {
-        Some(query_) => query_,
-        None => {
-            return ResponseCreator::create_bad_request();
+    match serde_json::to_string(&EventPage {
+        events: event_record_registry,
+        next_cursor,
+        total,
+    }) {
+        Ok(data) => {
+            return ResponseCreator::create_ok(Some(data));
         }
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
+    }
+}
+
+// The types are filtered by the comma separated 'type' parameter and the events by the 'collection'
+// parameter. The id of each event is the cursor of the stream, which is the cursor of the last event
+// of each type, like 'collection_created:5,token_minted:7'. The stream is resumed after the cursor
+// that comes in the 'Last-Event-ID' header.
+async fn get_event_stream<'a, S>(request: &'a Request<Body>, event_store: &'a S) -> Response<Body>
+where
+    S: EventStore,
+{
+    let query = request.uri().query().unwrap_or("");
+
+    let event_type_registry = match get_parameter_from_query::<String>(query.as_bytes(), "type") {
+        Some(event_type_registry_) => event_type_registry_
+            .split(',')
+            .map(|event_type| event_type.to_string())
+            .collect::<Vec<String>>(),
+        None => EventType::REGISTRY
+            .iter()
+            .map(|event_type| event_type.get_name().to_string())
+            .collect::<Vec<String>>(),
     };
 
-    let index_value = match get_parameter_from_query::<i64>(query.as_bytes(), "index") {
-        Some(value_) => value_,
-        None => {
-            return ResponseCreator::create_bad_request();
+    for event_type in event_type_registry.iter() {
+        if EventType::from_name(event_type.as_str()).is_none() {
+            return ResponseCreator::create_bad_request(
+                "The 'type' parameter contains an unknown event type.",
+                Some(json!({ "type": event_type })),
+            );
+        }
+    }
+
+    let collection = if is_parameter_in_query(request, "collection") {
+        match get_parameter_from_query::<Address>(query.as_bytes(), "collection") {
+            Some(collection_) => Some(format!("{:?}", collection_)),
+            None => {
+                return ResponseCreator::create_bad_request(
+                    "The 'collection' parameter must be an address.",
+                    None,
+                );
+            }
         }
+    } else {
+        None
     };
 
-    let mut pooled_connection = match connection_pool.get().await {
-        Ok(pooled_connection_) => pooled_connection_,
+    let mut event_cursor = match request.headers().get("last-event-id") {
+        Some(last_event_id) => match last_event_id.to_str().ok().and_then(parse_event_cursor) {
+            Some(event_cursor_) => event_cursor_,
+            None => {
+                return ResponseCreator::create_bad_request(
+                    "The 'Last-Event-ID' header must be a cursor of the stream.",
+                    None,
+                );
+            }
+        },
+        None => BTreeMap::new(),
+    };
+
+    event_cursor.retain(|event_type, _| event_type_registry.contains(event_type));
+
+    for (event_type, cursor) in event_cursor.iter() {
+        let event_type_ = match EventType::from_name(event_type.as_str()) {
+            Some(event_type__) => event_type__,
+            None => {
+                continue;
+            }
+        };
+
+        match event_store
+            .get_page(event_type_, EventPageStart::After(cursor.clone()), 1)
+            .await
+        {
+            Ok(_) => {}
+            Err(Error::NotFound { .. }) => {
+                return ResponseCreator::create_bad_request(
+                    "The 'Last-Event-ID' header must be a cursor of the stream.",
+                    None,
+                );
+            }
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        }
+    }
+
+    let event_notification_stream = match event_store.subscribe().await {
+        Ok(event_notification_stream_) => event_notification_stream_,
         Err(_) => {
             return ResponseCreator::create_internal_server_error();
         }
     };
 
-    let connection = &mut *pooled_connection;
+    // The stream starts after the last events of the types that are not resumed. The cursors are
+    // read after subscribing, so that no event falls between them.
+    for event_type in event_type_registry.iter() {
+        if event_cursor.contains_key(event_type) {
+            continue;
+        }
+
+        let event_type_ = match EventType::from_name(event_type.as_str()) {
+            Some(event_type__) => event_type__,
+            None => {
+                continue;
+            }
+        };
+
+        let cursor = match event_store.get_last_cursor(event_type_).await {
+            Ok(cursor_) => cursor_,
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        };
+
+        event_cursor.insert(event_type.clone(), cursor);
+    }
+
+    let (sender, body) = Body::channel();
+
+    tokio::spawn(stream_events(
+        sender,
+        event_notification_stream,
+        event_cursor,
+        event_type_registry,
+        collection,
+        event_store.clone(),
+    ));
+
+    return ResponseCreator::create_event_stream(body);
+}
+
+async fn stream_events<S>(
+    mut sender: Sender,
+    mut event_notification_stream: BoxStream<'static, EventNotification>,
+    mut event_cursor: BTreeMap<String, String>,
+    event_type_registry: Vec<String>,
+    collection: Option<String>,
+    event_store: S,
+) -> ()
+where
+    S: EventStore,
+{
+    // The events stored while the client was away are sent first. Their notifications may come after
+    // them, so the sent cursors are kept to skip these. The confirmed and retracted events are always sent.
+    let mut sent_cursor_registry: HashSet<(String, String)> = HashSet::new();
+
+    for event_type in event_type_registry.iter() {
+        let event_type_ = match EventType::from_name(event_type.as_str()) {
+            Some(event_type__) => event_type__,
+            None => {
+                continue;
+            }
+        };
+
+        while let Some(cursor) = event_cursor.get(event_type).cloned() {
+            let event_entry_registry = match event_store
+                .get_page(
+                    event_type_,
+                    EventPageStart::After(cursor),
+                    EVENT_PAGE_MAXIMUM_LIMIT,
+                )
+                .await
+            {
+                Ok(event_entry_registry_) => event_entry_registry_,
+                Err(_) => {
+                    return ();
+                }
+            };
+
+            if event_entry_registry.is_empty() {
+                break;
+            }
+
+            for event_entry in event_entry_registry.into_iter() {
+                let record = match serde_json::from_str::<Value>(event_entry.data.as_str()) {
+                    Ok(record_) => record_,
+                    Err(_) => {
+                        return ();
+                    }
+                };
+
+                sent_cursor_registry.insert((event_type.clone(), event_entry.cursor.clone()));
+
+                let event_notification = EventNotification {
+                    event_type: event_type.clone(),
+                    change: EventChange::Appended,
+                    cursor: event_entry.cursor,
+                    record,
+                };
+
+                if !send_event(
+                    &mut sender,
+                    &mut event_cursor,
+                    &event_notification,
+                    &collection,
+                )
+                .await
+                {
+                    return ();
+                }
+            }
+        }
+    }
+
+    let mut keep_alive_interval = time::interval(Duration::from_secs(
+        EVENT_STREAM_KEEP_ALIVE_INTERVAL_SECONDS,
+    ));
+
+    loop {
+        tokio::select! {
+            event_notification = event_notification_stream.next() => {
+                let event_notification_ = match event_notification {
+                    Some(event_notification__) => event_notification__,
+                    None => {
+                        return ();
+                    }
+                };
+
+                // The event is skipped if its type is filtered out or if it is sent already.
+                if !event_cursor.contains_key(&event_notification_.event_type)
+                    || (event_notification_.change == EventChange::Appended
+                        && sent_cursor_registry.remove(&(
+                            event_notification_.event_type.clone(),
+                            event_notification_.cursor.clone(),
+                        )))
+                {
+                    continue;
+                }
+
+                if !send_event(&mut sender, &mut event_cursor, &event_notification_, &collection).await {
+                    return ();
+                }
+            }
+            _ = keep_alive_interval.tick() => {
+                if sender.send_data(Bytes::from(": keep-alive\n\n")).await.is_err() {
+                    return ();
+                }
+            }
+        }
+    }
+}
+
+// Returns false if the client is gone. The appended event is named by its type, and the other changes
+// by themselves. Only the appended events move the cursor of the stream, because the confirmed and
+// retracted ones may be before it.
+async fn send_event<'a>(
+    sender: &'a mut Sender,
+    event_cursor: &'a mut BTreeMap<String, String>,
+    event_notification: &'a EventNotification,
+    collection: &'a Option<String>,
+) -> bool {
+    if event_notification.change == EventChange::Appended {
+        event_cursor.insert(
+            event_notification.event_type.clone(),
+            event_notification.cursor.clone(),
+        );
+    }
+
+    if let Some(ref collection_) = *collection {
+        let event_collection = event_notification.record["event"]["collection"].as_str();
+
+        if event_collection != Some(collection_.as_str()) {
+            return true;
+        }
+    }
+
+    let data = match serde_json::to_string(event_notification) {
+        Ok(data_) => data_,
+        Err(_) => {
+            return false;
+        }
+    };
+
+    let event_name = match event_notification.change {
+        EventChange::Appended => event_notification.event_type.as_str(),
+        change => change.get_name(),
+    };
 
-    let key_event = StorageKeyManager::create_key_event(
-        StorageKeyManager::EVENT_COLLECTION_CREATED_PART,
-        index_value,
+    let frame = format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        format_event_cursor(event_cursor),
+        event_name,
+        data
     );
 
-    let data = match connection
-        .get::<'_, &'_ str, Option<String>>(&key_event)
+    return sender.send_data(Bytes::from(frame)).await.is_ok();
+}
+
+// The type is before the first ':', because a cursor may contain it too.
+fn parse_event_cursor<'a>(event_cursor: &'a str) -> Option<BTreeMap<String, String>> {
+    let mut event_cursor_: BTreeMap<String, String> = BTreeMap::new();
+
+    for position in event_cursor.split(',') {
+        let (event_type, cursor) = position.split_once(':')?;
+
+        EventType::from_name(event_type)?;
+
+        event_cursor_.insert(event_type.to_string(), cursor.to_string());
+    }
+
+    return Some(event_cursor_);
+}
+
+fn format_event_cursor<'a>(event_cursor: &'a BTreeMap<String, String>) -> String {
+    return event_cursor
+        .iter()
+        .map(|(event_type, cursor)| format!("{}:{}", event_type, cursor))
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+async fn get_event_collection_created_quantity<'a, S>(event_store: &'a S) -> Response<Body>
+where
+    S: EventStore,
+{
+    match event_store.count(EventType::CollectionCreated).await {
+        Ok(quantity) => {
+            return ResponseCreator::create_ok(Some(quantity.to_string()));
+        }
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
+    }
+}
+
+// The event is addressed by its cursor or by its index, which is its position starting from 1 among
+// the saved events. Returns the event and the details of the not found response, or the response of
+// the bad request.
+async fn get_event<'a, S>(
+    request: &'a Request<Body>,
+    event_type: EventType,
+    event_store: &'a S,
+) -> Result<(Option<String>, Value), Response<Body>>
+where
+    S: EventStore,
+{
+    let query = request.uri().query().unwrap_or("");
+
+    if is_parameter_in_query(request, "cursor") && is_parameter_in_query(request, "index") {
+        return Err(ResponseCreator::create_bad_request(
+            "The 'cursor' and 'index' parameters can not be used together.",
+            None,
+        ));
+    }
+
+    if is_parameter_in_query(request, "cursor") {
+        let cursor = match get_parameter_from_query::<String>(query.as_bytes(), "cursor") {
+            Some(cursor_) => cursor_,
+            None => {
+                return Err(ResponseCreator::create_bad_request(
+                    "The 'cursor' parameter is required.",
+                    None,
+                ));
+            }
+        };
+
+        match event_store.get_by_cursor(event_type, cursor.as_str()).await {
+            Ok(data) => {
+                return Ok((data, json!({ "cursor": cursor })));
+            }
+            Err(_) => {
+                return Err(ResponseCreator::create_internal_server_error());
+            }
+        }
+    }
+
+    let index = match get_parameter_from_query::<i64>(query.as_bytes(), "index") {
+        Some(index_) if index_ > 0 => index_,
+        _ => {
+            return Err(ResponseCreator::create_bad_request(
+                "The 'index' parameter must be a positive integer.",
+                None,
+            ));
+        }
+    };
+
+    match event_store
+        .get_page(event_type, EventPageStart::Offset(index - 1), 1)
         .await
     {
-        Ok(data_) => data_,
+        Ok(event_entry_registry) => {
+            return Ok((
+                event_entry_registry
+                    .into_iter()
+                    .next()
+                    .map(|event_entry| event_entry.data),
+                json!({ "index": index }),
+            ));
+        }
         Err(_) => {
-            return ResponseCreator::create_internal_server_error();
+            return Err(ResponseCreator::create_internal_server_error());
+        }
+    }
+}
+
+async fn get_event_collection_created<'a, S>(
+    request: Request<Body>,
+    event_store: &'a S,
+) -> Response<Body>
+where
+    S: EventStore,
+{
+    let (data, details) = match get_event(&request, EventType::CollectionCreated, event_store).await
+    {
+        Ok(event) => event,
+        Err(response) => {
+            return response;
         }
     };
 
     match data {
         Some(data_) => {
-            return ResponseCreator::create_ok(Some(data_));
+            let event_record =
+                match serde_json::from_str::<EventRecord<CollectionCreatedEvent>>(data_.as_str()) {
+                    Ok(event_record_) => event_record_,
+                    Err(_) => {
+                        return ResponseCreator::create_internal_server_error();
+                    }
+                };
+
+            let data__ = match serde_json::to_string(&event_record) {
+                Ok(data___) => data___,
+                Err(_) => {
+                    return ResponseCreator::create_internal_server_error();
+                }
+            };
+
+            return ResponseCreator::create_ok(Some(data__));
         }
         None => {
-            return ResponseCreator::create_ok(Some(format!(
-                "There are no event of CreateCollection type with index {}.",
-                index_value
-            )))
+            return ResponseCreator::create_not_found("The event is not found.", Some(details));
         }
     }
 }
 
-async fn get_event_token_minted_quantity<'a>(
-    connection_pool: &'a Pool<RedisConnectionManager>,
-) -> Response<Body> {
-    let mut pooled_connection = match connection_pool.get().await {
-        Ok(pooled_connection_) => pooled_connection_,
+async fn get_event_token_minted_quantity<'a, S>(event_store: &'a S) -> Response<Body>
+where
+    S: EventStore,
+{
+    match event_store.count(EventType::TokenMinted).await {
+        Ok(quantity) => {
+            return ResponseCreator::create_ok(Some(quantity.to_string()));
+        }
         Err(_) => {
             return ResponseCreator::create_internal_server_error();
         }
-    };
-
-    let connection = &mut *pooled_connection;
+    }
+}
 
-    let quantity = match connection
-        .get::<'_, &'_ str, Option<String>>(StorageKeyManager::EVENT_TOKEN_MINTED_QUANTITY)
-        .await
-    {
-        Ok(quantity_) => quantity_,
-        Err(_) => {
-            return ResponseCreator::create_internal_server_error();
+async fn get_event_token_minted<'a, S>(request: Request<Body>, event_store: &'a S) -> Response<Body>
+where
+    S: EventStore,
+{
+    let (data, details) = match get_event(&request, EventType::TokenMinted, event_store).await {
+        Ok(event) => event,
+        Err(response) => {
+            return response;
         }
     };
 
-    match quantity {
-        Some(quantity_) => {
-            return ResponseCreator::create_ok(Some(quantity_));
+    match data {
+        Some(data_) => {
+            let event_record =
+                match serde_json::from_str::<EventRecord<TokenMintedEvent>>(data_.as_str()) {
+                    Ok(event_record_) => event_record_,
+                    Err(_) => {
+                        return ResponseCreator::create_internal_server_error();
+                    }
+                };
+
+            let data__ = match serde_json::to_string(&event_record) {
+                Ok(data___) => data___,
+                Err(_) => {
+                    return ResponseCreator::create_internal_server_error();
+                }
+            };
+
+            return ResponseCreator::create_ok(Some(data__));
         }
         None => {
-            return ResponseCreator::create_ok(Some(
-                "There are no events of TokenMinted type yet.".to_string(),
-            ));
+            return ResponseCreator::create_not_found("The event is not found.", Some(details));
         }
     }
 }
 
-async fn get_event_token_minted_by_index<'a>(
-    request: Request<Body>,
-    connection_pool: &'a Pool<RedisConnectionManager>,
-) -> Response<Body> {
-    let query = match request.uri().query() {
-        Some(query_) => query_,
-        None => {
-            return ResponseCreator::create_bad_request();
+// The collections are paged in the order of the registry of the contract, if it is configured, and in
+// the order of the index otherwise.
+async fn get_collection_page<'a, S>(
+    request: &'a Request<Body>,
+    event_store: &'a S,
+    collection_registry: &'a Option<CollectionRegistry>,
+) -> Response<Body>
+where
+    S: EventStore,
+{
+    let (page_start, limit) = match get_page_parameters(request) {
+        Ok(page_parameters) => page_parameters,
+        Err(message) => {
+            return ResponseCreator::create_bad_request(message.as_str(), None);
         }
     };
 
-    let index_value = match get_parameter_from_query::<i64>(query.as_bytes(), "index") {
-        Some(value_) => value_,
+    let total = match collection_registry {
+        Some(collection_registry_) => {
+            match CollectionRegistryReader::read_length(
+                &collection_registry_.web3,
+                collection_registry_.collection_aggregator_address,
+            )
+            .await
+            {
+                Ok(length) => match i64::try_from(length) {
+                    Ok(length_) => length_,
+                    Err(_) => {
+                        return ResponseCreator::create_internal_server_error();
+                    }
+                },
+                Err(_) => {
+                    return ResponseCreator::create_internal_server_error();
+                }
+            }
+        }
+        None => match event_store.count(EventType::CollectionCreated).await {
+            Ok(quantity) => quantity,
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        },
+    };
+
+    let mut collection_address_registry: Vec<Address> = vec![];
+
+    let next_cursor = match collection_registry {
+        Some(collection_registry_) => {
+            let after_index = match get_registry_index(&page_start) {
+                Some(after_index_) => after_index_,
+                None => {
+                    return ResponseCreator::create_bad_request(
+                        "The 'after' parameter must be a cursor of the page.",
+                        None,
+                    );
+                }
+            };
+
+            let last_index = cmp::min(after_index.saturating_add(limit), total);
+
+            for index in after_index..last_index {
+                match CollectionRegistryReader::read_by_index(
+                    &collection_registry_.web3,
+                    collection_registry_.collection_aggregator_address,
+                    U256::from(index),
+                )
+                .await
+                {
+                    Ok(collection) => {
+                        collection_address_registry.push(collection);
+                    }
+                    Err(_) => {
+                        return ResponseCreator::create_internal_server_error();
+                    }
+                }
+            }
+
+            if last_index < total {
+                Some(last_index.to_string())
+            } else {
+                None
+            }
+        }
         None => {
-            return ResponseCreator::create_bad_request();
+            let mut event_entry_registry = match event_store
+                .get_page(EventType::CollectionCreated, page_start, limit + 1)
+                .await
+            {
+                Ok(event_entry_registry_) => event_entry_registry_,
+                Err(Error::NotFound { .. }) => {
+                    return ResponseCreator::create_bad_request(
+                        "The 'after' parameter must be a cursor of the page.",
+                        None,
+                    );
+                }
+                Err(_) => {
+                    return ResponseCreator::create_internal_server_error();
+                }
+            };
+
+            let next_cursor = if event_entry_registry.len() as i64 > limit {
+                event_entry_registry.truncate(limit as usize);
+
+                event_entry_registry
+                    .last()
+                    .map(|event_entry| event_entry.cursor.clone())
+            } else {
+                None
+            };
+
+            for event_entry in event_entry_registry.into_iter() {
+                match serde_json::from_str::<EventRecord<CollectionCreatedEvent>>(
+                    event_entry.data.as_str(),
+                ) {
+                    Ok(event_record) => {
+                        collection_address_registry.push(event_record.event.collection);
+                    }
+                    Err(_) => {
+                        return ResponseCreator::create_internal_server_error();
+                    }
+                }
+            }
+
+            next_cursor
+        }
+    };
+
+    let mut collection_summary_registry: Vec<CollectionSummary> = vec![];
+
+    for collection in collection_address_registry.into_iter() {
+        match get_collection_summary(collection, event_store, collection_registry).await {
+            Ok(Some(collection_summary)) => {
+                collection_summary_registry.push(collection_summary);
+            }
+            Ok(None) => {}
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        }
+    }
+
+    match serde_json::to_string(&CollectionPage {
+        collections: collection_summary_registry,
+        next_cursor,
+        total,
+    }) {
+        Ok(data) => {
+            return ResponseCreator::create_ok(Some(data));
+        }
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
+    }
+}
+
+async fn get_collection<'a, S>(
+    collection: &'a str,
+    event_store: &'a S,
+    collection_registry: &'a Option<CollectionRegistry>,
+) -> Response<Body>
+where
+    S: EventStore,
+{
+    let collection_ = match Address::from_str(collection) {
+        Ok(collection__) => collection__,
+        Err(_) => {
+            return ResponseCreator::create_bad_request(
+                "The collection must be an address.",
+                Some(json!({ "collection": collection })),
+            );
         }
     };
 
-    let mut pooled_connection = match connection_pool.get().await {
-        Ok(pooled_connection_) => pooled_connection_,
+    // The index may keep a collection of a removed block that the contract does not have.
+    if let Some(collection_registry_) = collection_registry {
+        match CollectionRegistryReader::is_exist(
+            &collection_registry_.web3,
+            collection_registry_.collection_aggregator_address,
+            collection_,
+        )
+        .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return ResponseCreator::create_not_found(
+                    "The collection is not found.",
+                    Some(json!({ "collection": collection })),
+                );
+            }
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        }
+    }
+
+    let collection_summary =
+        match get_collection_summary(collection_, event_store, collection_registry).await {
+            Ok(Some(collection_summary_)) => collection_summary_,
+            Ok(None) => {
+                return ResponseCreator::create_not_found(
+                    "The collection is not found.",
+                    Some(json!({ "collection": collection })),
+                );
+            }
+            Err(_) => {
+                return ResponseCreator::create_internal_server_error();
+            }
+        };
+
+    match serde_json::to_string(&collection_summary) {
+        Ok(data) => {
+            return ResponseCreator::create_ok(Some(data));
+        }
         Err(_) => {
             return ResponseCreator::create_internal_server_error();
         }
+    }
+}
+
+// The collection that is missing in the index is read from the contract, if the registry is configured.
+// The mints are counted in the index only.
+async fn get_collection_summary<'a, S>(
+    collection: Address,
+    event_store: &'a S,
+    collection_registry: &'a Option<CollectionRegistry>,
+) -> Result<Option<CollectionSummary>, Error>
+where
+    S: EventStore,
+{
+    let event_entry_registry = event_store
+        .get_indexed_page(
+            EventType::CollectionCreated,
+            EventIndex::Collection(collection),
+            EventPageStart::Offset(0),
+            1,
+        )
+        .await?;
+
+    let mint_quantity = event_store
+        .count_indexed(EventType::TokenMinted, EventIndex::Collection(collection))
+        .await?;
+
+    if let Some(data) = event_entry_registry
+        .into_iter()
+        .next()
+        .map(|event_entry| event_entry.data)
+    {
+        let event_record =
+            match serde_json::from_str::<EventRecord<CollectionCreatedEvent>>(data.as_str()) {
+                Ok(event_record_) => event_record_,
+                Err(error) => {
+                    return Err(Error::Decoding {
+                        context: "Failed to decode the saved 'collection_created' event"
+                            .to_string(),
+                        source: Some(Box::from(error)),
+                    });
+                }
+            };
+
+        return Ok(Some(CollectionSummary {
+            collection,
+            name: event_record.event.name,
+            symbol: event_record.event.symbol,
+            block_number: Some(event_record.event.block_number),
+            mint_quantity,
+            is_indexed: true,
+        }));
+    }
+
+    let collection_registry_ = match collection_registry {
+        Some(collection_registry__) => collection_registry__,
+        None => {
+            return Ok(None);
+        }
     };
 
-    let connection = &mut *pooled_connection;
+    let (name, symbol) =
+        CollectionMetadataReader::read(&collection_registry_.web3, collection).await?;
 
-    let key_event = StorageKeyManager::create_key_event(
-        StorageKeyManager::EVENT_TOKEN_MINTED_PART,
-        index_value,
-    );
+    return Ok(Some(CollectionSummary {
+        collection,
+        name,
+        symbol,
+        block_number: None,
+        mint_quantity,
+        is_indexed: false,
+    }));
+}
+
+async fn get_token_ownership<'a, S>(
+    collection: &'a str,
+    token_id: &'a str,
+    event_store: &'a S,
+) -> Response<Body>
+where
+    S: EventStore,
+{
+    let collection_ = match Address::from_str(collection) {
+        Ok(collection__) => collection__,
+        Err(_) => {
+            return ResponseCreator::create_bad_request(
+                "The collection must be an address.",
+                Some(json!({ "collection": collection })),
+            );
+        }
+    };
+
+    let token_id_ = match U256::from_dec_str(token_id) {
+        Ok(token_id__) => token_id__,
+        Err(_) => {
+            return ResponseCreator::create_bad_request(
+                "The token id must be a decimal integer.",
+                Some(json!({ "token_id": token_id })),
+            );
+        }
+    };
 
-    let data = match connection
-        .get::<'_, &'_ str, Option<String>>(&key_event)
+    let token_ownership = match event_store
+        .get_token_ownership(collection_, token_id_)
         .await
     {
-        Ok(data_) => data_,
+        Ok(Some(token_ownership_)) => token_ownership_,
+        Ok(None) => {
+            return ResponseCreator::create_not_found(
+                "The token is not found.",
+                Some(json!({ "collection": collection, "token_id": token_id })),
+            );
+        }
         Err(_) => {
             return ResponseCreator::create_internal_server_error();
         }
     };
 
-    match data {
-        Some(data_) => {
-            return ResponseCreator::create_ok(Some(data_));
+    // The burned token has no owner.
+    if token_ownership.owner.is_zero() {
+        return ResponseCreator::create_not_found(
+            "The token is burned.",
+            Some(json!({ "collection": collection, "token_id": token_id })),
+        );
+    }
+
+    match serde_json::to_string(&token_ownership) {
+        Ok(data) => {
+            return ResponseCreator::create_ok(Some(data));
         }
-        None => {
-            return ResponseCreator::create_ok(Some(format!(
-                "There are no event of TokenMinted type with index {}.",
-                index_value
-            )))
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
         }
     }
 }
 
-#[derive(Debug)]
-struct LogicError;
+async fn get_owned_token_registry<'a, S>(owner: &'a str, event_store: &'a S) -> Response<Body>
+where
+    S: EventStore,
+{
+    let owner_ = match Address::from_str(owner) {
+        Ok(owner__) => owner__,
+        Err(_) => {
+            return ResponseCreator::create_bad_request(
+                "The owner must be an address.",
+                Some(json!({ "owner": owner })),
+            );
+        }
+    };
+
+    let owned_token_registry = match event_store.get_owned_token_registry(owner_).await {
+        Ok(owned_token_registry_) => owned_token_registry_,
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
+    };
 
-impl Display for LogicError {
-    fn fmt<'a>(&'a self, formatter: &'a mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(formatter, "Logic error.")
+    match serde_json::to_string(&owned_token_registry) {
+        Ok(data) => {
+            return ResponseCreator::create_ok(Some(data));
+        }
+        Err(_) => {
+            return ResponseCreator::create_internal_server_error();
+        }
     }
 }
 
-impl Error for LogicError {}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use infrastructure::hyper::body;
+    use infrastructure::hyper::body::HttpBody;
+    use infrastructure::hyper::StatusCode;
+    use infrastructure::web3::types::H256;
+    use infrastructure::EventCheckpoint;
+    use infrastructure::EventStatus;
+    use infrastructure::InMemoryEventStore;
+    use infrastructure::TokenOwnership;
+
+    fn create_request<'a>(query: &'a str) -> Request<Body> {
+        return Request::get(format!("http://127.0.0.1/event/token_minted?{}", query))
+            .body(Body::empty())
+            .unwrap();
+    }
+
+    // The event is final and the first one of its block, with its block number as the transaction hash.
+    async fn append_event<'a, T>(
+        event_store: &'a InMemoryEventStore,
+        event_type: EventType,
+        block_number: u64,
+        event: T,
+    ) -> ()
+    where
+        T: Serialize,
+    {
+        event_store
+            .append(
+                Address::repeat_byte(9),
+                event_type,
+                EventCheckpoint {
+                    block_number,
+                    log_index: 0,
+                },
+                H256::from_low_u64_be(block_number),
+                EventStatus::Final,
+                serde_json::to_string(&EventRecord {
+                    status: EventStatus::Final,
+                    event,
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        return ();
+    }
+
+    // The token id is the block number.
+    async fn append_token_minted<'a>(
+        event_store: &'a InMemoryEventStore,
+        collection: Address,
+        block_number: u64,
+    ) -> () {
+        return append_event(
+            event_store,
+            EventType::TokenMinted,
+            block_number,
+            TokenMintedEvent {
+                collection,
+                recipient: Address::repeat_byte(2),
+                token_id: U256::from(block_number),
+                token_uri: format!("ipfs://{}", block_number),
+                block_number,
+                tx_hash: H256::from_low_u64_be(block_number),
+                log_index: 0,
+            },
+        )
+        .await;
+    }
+
+    async fn get<'a>(event_store: &'a InMemoryEventStore, uri: &'a str) -> (StatusCode, Value) {
+        let response = resolve(
+            Request::get(format!("http://127.0.0.1{}", uri))
+                .body(Body::empty())
+                .unwrap(),
+            event_store,
+            &None,
+        )
+        .await;
+
+        let status_code = response.status();
+
+        let data = body::to_bytes(response.into_body()).await.unwrap();
+
+        return (status_code, serde_json::from_slice::<Value>(&data).unwrap());
+    }
+
+    #[test]
+    fn page_parameters_have_defaults() -> () {
+        let (page_start, limit) = get_page_parameters(&create_request("")).unwrap();
+
+        assert!(matches!(page_start, EventPageStart::Offset(0)));
+        assert_eq!(limit, EVENT_PAGE_DEFAULT_LIMIT);
+
+        return ();
+    }
+
+    #[test]
+    fn page_parameters_start_at_offset_or_after_cursor() -> () {
+        let (page_start, limit) =
+            get_page_parameters(&create_request("offset=5&limit=10")).unwrap();
+
+        assert!(matches!(page_start, EventPageStart::Offset(5)));
+        assert_eq!(limit, 10);
+
+        let (page_start, _) =
+            get_page_parameters(&create_request("after=1700000000000-0")).unwrap();
+
+        assert!(
+            matches!(page_start, EventPageStart::After(ref cursor) if cursor == "1700000000000-0")
+        );
+
+        return ();
+    }
+
+    #[test]
+    fn page_parameters_reject_invalid_values() -> () {
+        assert!(get_page_parameters(&create_request("limit=0")).is_err());
+        assert!(get_page_parameters(&create_request(
+            format!("limit={}", EVENT_PAGE_MAXIMUM_LIMIT + 1).as_str()
+        ))
+        .is_err());
+        assert!(get_page_parameters(&create_request("offset=-1")).is_err());
+        assert!(get_page_parameters(&create_request("offset=x")).is_err());
+        assert!(get_page_parameters(&create_request("offset=1&after=2")).is_err());
+
+        return ();
+    }
+
+    #[test]
+    fn event_cursor_is_parsed_by_type() -> () {
+        let event_cursor =
+            parse_event_cursor("collection_created:5,token_minted:1700000000000-0").unwrap();
+
+        assert_eq!(event_cursor.len(), 2);
+        assert_eq!(event_cursor["collection_created"], "5");
+        assert_eq!(event_cursor["token_minted"], "1700000000000-0");
+        assert_eq!(
+            format_event_cursor(&event_cursor),
+            "collection_created:5,token_minted:1700000000000-0"
+        );
+
+        assert!(parse_event_cursor("unknown_type:5").is_none());
+        assert!(parse_event_cursor("token_minted").is_none());
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn event_is_found_by_index_or_cursor() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        for block_number in 1..=3 {
+            append_token_minted(&event_store, Address::repeat_byte(1), block_number).await;
+        }
+
+        let (status_code, data) = get(&event_store, "/event/token_minted?index=2").await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(data["event"]["block_number"], 2);
+
+        let (status_code, data) = get(&event_store, "/event/token_minted?cursor=3").await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(data["event"]["block_number"], 3);
+
+        let (status_code, data) = get(&event_store, "/event/token_minted?index=4").await;
+
+        assert_eq!(status_code, StatusCode::NOT_FOUND);
+        assert_eq!(data["details"]["index"], 4);
+
+        let (status_code, _) = get(&event_store, "/event/token_minted?index=0").await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+
+        let (status_code, _) = get(&event_store, "/event/token_minted?index=1&cursor=1").await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn token_ownership_is_found_by_token_and_owner() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let (collection, owner) = (Address::repeat_byte(1), Address::repeat_byte(4));
+
+        for (token_id, owner_, block_number) in
+            [(7, owner, 5), (8, owner, 6), (8, Address::zero(), 7)]
+        {
+            event_store
+                .index_ownership(TokenOwnership {
+                    collection,
+                    token_id: U256::from(token_id),
+                    owner: owner_,
+                    block_number,
+                    log_index: 0,
+                })
+                .await
+                .unwrap();
+        }
+
+        let (status_code, data) = get(
+            &event_store,
+            format!("/collection/{:?}/token/7", collection).as_str(),
+        )
+        .await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(data["owner"], format!("{:?}", owner));
+        assert_eq!(data["block_number"], 5);
+
+        // The burned token and the unknown one are not found.
+        for token_id in ["8", "9"] {
+            let (status_code, _) = get(
+                &event_store,
+                format!("/collection/{:?}/token/{}", collection, token_id).as_str(),
+            )
+            .await;
+
+            assert_eq!(status_code, StatusCode::NOT_FOUND);
+        }
+
+        let (status_code, data) = get(
+            &event_store,
+            format!("/collection/{:?}/token/x", collection).as_str(),
+        )
+        .await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(data["details"]["token_id"], "x");
+
+        let (status_code, data) =
+            get(&event_store, format!("/owner/{:?}/tokens", owner).as_str()).await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(
+            data,
+            json!([{ "collection": collection, "token_id": U256::from(7) }])
+        );
+
+        let (status_code, _) = get(&event_store, "/owner/x/tokens").await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn collections_follow_saved_events_without_registry() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let collection_registry = [Address::repeat_byte(1), Address::repeat_byte(2)];
+
+        for (block_number, collection) in [1, 2].into_iter().zip(collection_registry.into_iter()) {
+            append_event(
+                &event_store,
+                EventType::CollectionCreated,
+                block_number,
+                CollectionCreatedEvent {
+                    collection,
+                    name: format!("Collection {}", block_number),
+                    symbol: format!("C{}", block_number),
+                    block_number,
+                    tx_hash: H256::from_low_u64_be(block_number),
+                    log_index: 0,
+                },
+            )
+            .await;
+        }
+
+        for block_number in 3..=4 {
+            append_token_minted(&event_store, collection_registry[0], block_number).await;
+        }
+
+        let (status_code, data) = get(&event_store, "/collections?limit=1").await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(data["total"], 2);
+        assert_eq!(
+            data["collections"],
+            json!([{
+                "collection": collection_registry[0],
+                "name": "Collection 1",
+                "symbol": "C1",
+                "block_number": 1,
+                "mint_quantity": 2,
+                "is_indexed": true,
+            }])
+        );
+
+        let (status_code, data) = get(
+            &event_store,
+            format!(
+                "/collections?after={}",
+                data["next_cursor"].as_str().unwrap()
+            )
+            .as_str(),
+        )
+        .await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(
+            data["collections"][0]["collection"],
+            json!(collection_registry[1])
+        );
+        assert_eq!(data["collections"][0]["mint_quantity"], 0);
+        assert_eq!(data["next_cursor"], Value::Null);
+
+        let (status_code, data) = get(
+            &event_store,
+            format!("/collection/{:?}", collection_registry[1]).as_str(),
+        )
+        .await;
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(data["name"], "Collection 2");
+
+        let (status_code, _) = get(
+            &event_store,
+            format!("/collection/{:?}", Address::repeat_byte(3)).as_str(),
+        )
+        .await;
+
+        assert_eq!(status_code, StatusCode::NOT_FOUND);
+
+        let (status_code, _) = get(&event_store, "/collection/x").await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+
+        return ();
+    }
+
+    // The keep-alive comments are skipped.
+    async fn read_event_frame<'a>(body: &'a mut Body) -> String {
+        loop {
+            let data = time::timeout(Duration::from_secs(5), body.data())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+
+            let frame = String::from_utf8(data.to_vec()).unwrap();
+
+            if !frame.starts_with(':') {
+                return frame;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn event_stream_resumes_and_sends_changes() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let collection = Address::repeat_byte(1);
+
+        for block_number in 1..=2 {
+            append_token_minted(&event_store, collection, block_number).await;
+        }
+
+        let response = resolve(
+            Request::get("http://127.0.0.1/event/stream?type=token_minted")
+                .header("last-event-id", "token_minted:1")
+                .body(Body::empty())
+                .unwrap(),
+            &event_store,
+            &None,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut body = response.into_body();
+
+        // The event stored after the cursor is sent first.
+        let frame = read_event_frame(&mut body).await;
+
+        assert!(frame.starts_with("id: token_minted:2\nevent: token_minted\ndata: "));
+
+        append_token_minted(&event_store, collection, 3).await;
+
+        let frame = read_event_frame(&mut body).await;
+
+        assert!(frame.starts_with("id: token_minted:3\nevent: token_minted\n"));
+
+        // The retracted event does not move the cursor of the stream.
+        event_store
+            .retract(
+                EventType::TokenMinted,
+                EventCheckpoint {
+                    block_number: 3,
+                    log_index: 0,
+                },
+                H256::from_low_u64_be(3),
+            )
+            .await
+            .unwrap();
+
+        let frame = read_event_frame(&mut body).await;
+
+        assert!(frame.starts_with("id: token_minted:3\nevent: retracted\n"));
+
+        let data = serde_json::from_str::<Value>(frame.split_once("data: ").unwrap().1).unwrap();
+
+        assert_eq!(data["cursor"], "3");
+        assert_eq!(data["record"]["status"], "removed");
+
+        let (status_code, _) = get(&event_store, "/event/stream?type=unknown_type").await;
+
+        assert_eq!(status_code, StatusCode::BAD_REQUEST);
+
+        return ();
+    }
+}
@@ -0,0 +1,97 @@
+-- The statements are valid for both PostgreSQL and SQLite.
+-- The addresses and hashes are 0x-prefixed lowercase hex, the token ids are decimal.
+
+CREATE TABLE events (
+    event_type TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    record TEXT NOT NULL,
+    PRIMARY KEY (event_type, position)
+);
+
+-- A retracted event is kept with the removed status, so that its position is never taken again. The log
+-- is stored at a new position if a later block includes it.
+CREATE UNIQUE INDEX events_log_index ON events (event_type, tx_hash, log_index) WHERE status <> 'removed';
+-- Every appended, confirmed and retracted event is kept with its record at that time, so that the subscribers
+-- poll the changes in order.
+CREATE TABLE event_changes (
+    sequence BIGINT NOT NULL PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    change TEXT NOT NULL,
+    record TEXT NOT NULL
+);
+
+CREATE TABLE event_sequence (
+    value BIGINT NOT NULL
+);
+
+INSERT INTO event_sequence (value) VALUES (0);
+
+CREATE TABLE checkpoints (
+    contract_address TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    PRIMARY KEY (contract_address, event_type)
+);
+
+CREATE TABLE collections (
+    address TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index BIGINT NOT NULL,
+    status TEXT NOT NULL
+);
+
+CREATE INDEX collections_block_number_index ON collections (block_number);
+
+CREATE TABLE mints (
+    tx_hash TEXT NOT NULL,
+    log_index BIGINT NOT NULL,
+    collection TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    token_uri TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    PRIMARY KEY (tx_hash, log_index)
+);
+
+CREATE INDEX mints_collection_index ON mints (collection);
+CREATE INDEX mints_recipient_index ON mints (recipient);
+CREATE INDEX mints_block_number_index ON mints (block_number);
+
+CREATE TABLE transfers (
+    tx_hash TEXT NOT NULL,
+    log_index BIGINT NOT NULL,
+    collection TEXT NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    PRIMARY KEY (tx_hash, log_index)
+);
+
+CREATE INDEX transfers_collection_index ON transfers (collection);
+CREATE INDEX transfers_from_address_index ON transfers (from_address);
+CREATE INDEX transfers_to_address_index ON transfers (to_address);
+CREATE INDEX transfers_block_number_index ON transfers (block_number);
+
+-- The current owner of every token, which is the zero address after the token is burned.
+CREATE TABLE tokens (
+    collection TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    PRIMARY KEY (collection, token_id)
+);
+
+CREATE INDEX tokens_owner_index ON tokens (owner);
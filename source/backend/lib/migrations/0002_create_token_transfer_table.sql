@@ -0,0 +1,13 @@
+-- Every indexed transfer of a token, so that the ownership is the latest remaining one after a retraction.
+CREATE TABLE token_transfers (
+    collection TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    PRIMARY KEY (collection, token_id, block_number, log_index)
+);
+
+-- The ownership that is indexed before is kept as the first transfer.
+INSERT INTO token_transfers (collection, token_id, owner, block_number, log_index)
+SELECT collection, token_id, owner, block_number, log_index FROM tokens;
@@ -0,0 +1,8 @@
+-- The last position of every event type, which the writers take the next one from under the lock of its row.
+CREATE TABLE event_positions (
+    event_type TEXT NOT NULL PRIMARY KEY,
+    value BIGINT NOT NULL
+);
+
+INSERT INTO event_positions (event_type, value)
+SELECT event_type, MAX(position) FROM events GROUP BY event_type;
@@ -70,7 +70,10 @@
     clippy::zero_sized_map_values
 )]
 
+use async_trait::async_trait;
 use bb8::Pool;
+use bb8::PooledConnection;
+use bb8::RunError;
 use bb8_redis::RedisConnectionManager;
 use http::header;
 use http::HeaderMap;
@@ -79,16 +82,74 @@ use http::Version;
 use hyper::Body;
 use hyper::Response;
 use hyper::StatusCode;
+use rand::Rng;
+use redis::aio::Connection as RedisConnection;
+use redis::aio::PubSub;
+use redis::streams::StreamRangeReply;
+use redis::AsyncCommands;
+use redis::Client;
 use redis::ConnectionInfo;
+use redis::RedisError;
+use redis::Script;
+use redis::ScriptInvocation;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Error as SerdeJsonError;
+use serde_json::Value;
+use sqlx::any::AnyKind;
+use sqlx::any::AnyPool;
+use sqlx::any::AnyPoolOptions;
+use sqlx::migrate::Migrator;
+use sqlx::Any;
+use sqlx::Error as SqlxError;
+use sqlx::Row;
+use sqlx::Transaction;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::convert::From;
-use std::error::Error;
+use std::env;
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 use web3::api::SubscriptionStream;
+use web3::contract::Contract as Web3Contract;
+use web3::contract::Error as Web3ContractError;
+use web3::contract::Options;
+use web3::ethabi::Contract;
+use web3::ethabi::LogParam;
+use web3::ethabi::RawLog;
+use web3::ethabi::Token;
+use web3::futures::stream;
+use web3::futures::stream::BoxStream;
+use web3::futures::StreamExt;
+use web3::signing::keccak256;
 use web3::transports::WebSocket;
 use web3::types::Address;
+use web3::types::BlockHeader;
+use web3::types::BlockNumber;
+use web3::types::Bytes;
 use web3::types::FilterBuilder;
 use web3::types::Log;
 use web3::types::H256;
+use web3::types::U256;
+use web3::types::U64;
+use web3::Error as Web3Error;
+use web3::Transport;
 use web3::Web3;
 
 pub use bb8;
@@ -96,27 +157,414 @@ pub use bb8_redis;
 pub use http;
 pub use hyper;
 pub use redis;
+pub use serde;
+pub use serde_json;
 pub use web3;
 
+// Every field has a default that points to the docker-compose services.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Configuration {
+    pub redis: RedisConfiguration,
+    pub ethereum: EthereumConfiguration,
+    pub server: ServerConfiguration,
+    pub contract: ContractConfiguration,
+    pub storage: StorageConfiguration,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedisConfiguration {
+    pub url: String,
+}
+
+impl Default for RedisConfiguration {
+    fn default() -> Self {
+        return Self {
+            url: "redis://redis".to_string(),
+        };
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EthereumConfiguration {
+    pub websocket_url: String,
+    pub http_url: String,
+}
+
+impl Default for EthereumConfiguration {
+    fn default() -> Self {
+        return Self {
+            websocket_url: "ws://ganache:8545".to_string(),
+            http_url: "http://ganache:8545".to_string(),
+        };
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfiguration {
+    pub socket_address: String,
+}
+
+impl Default for ServerConfiguration {
+    fn default() -> Self {
+        return Self {
+            socket_address: "0.0.0.0:80".to_string(),
+        };
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContractConfiguration {
+    pub metadata_file_path: String,
+    // The address of the deployed CollectionAggregator, which is optional.
+    pub address: String,
+    pub deployments_file_path: String,
+}
+
+impl Default for ContractConfiguration {
+    fn default() -> Self {
+        return Self {
+            metadata_file_path: "/_task/source/contract/build/contracts/CollectionAggregator.json"
+                .to_string(),
+            address: String::new(),
+            deployments_file_path: "/_task/source/contract/build/deployments.json".to_string(),
+        };
+    }
+}
+
+impl ContractConfiguration {
+    // The address is validated when the configuration is loaded, so only the empty one is None.
+    pub fn get_address(&self) -> Option<Address> {
+        return Address::from_str(self.address.as_str()).ok();
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfiguration {
+    pub backend: StorageBackend,
+    pub mode: StorageMode,
+    pub sql_url: String,
+}
+
+impl Default for StorageConfiguration {
+    fn default() -> Self {
+        return Self {
+            backend: StorageBackend::default(),
+            mode: StorageMode::default(),
+            sql_url: "sqlite://erc721.db?mode=rwc".to_string(),
+        };
+    }
+}
+
+// The mode is used by the Redis backend, the URL by the SQL one.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    Redis,
+    Sql,
+}
+
+impl Default for StorageBackend {
+    fn default() -> Self {
+        return Self::Redis;
+    }
+}
+
+impl FromStr for StorageBackend {
+    type Err = Error;
+
+    fn from_str<'a>(value: &'a str) -> Result<Self, Self::Err> {
+        match value {
+            "redis" => {
+                return Ok(Self::Redis);
+            }
+            "sql" => {
+                return Ok(Self::Sql);
+            }
+            _ => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Invalid 'storage.backend' '{}', expected 'redis' or 'sql'",
+                        value
+                    ),
+                    source: None,
+                });
+            }
+        }
+    }
+}
+
+// The events are stored under numbered keys or appended to a Redis stream per event type.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageMode {
+    Keys,
+    Streams,
+}
+
+impl Default for StorageMode {
+    fn default() -> Self {
+        return Self::Keys;
+    }
+}
+
+impl FromStr for StorageMode {
+    type Err = Error;
+
+    fn from_str<'a>(value: &'a str) -> Result<Self, Self::Err> {
+        match value {
+            "keys" => {
+                return Ok(Self::Keys);
+            }
+            "streams" => {
+                return Ok(Self::Streams);
+            }
+            _ => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Invalid 'storage.mode' '{}', expected 'keys' or 'streams'",
+                        value
+                    ),
+                    source: None,
+                });
+            }
+        }
+    }
+}
+
+pub struct ConfigurationLoader;
+
+impl ConfigurationLoader {
+    const ENVIRONMENT_CONFIGURATION_FILE_PATH: &'static str = "ERC721_CONFIGURATION_FILE_PATH";
+    const ENVIRONMENT_CONTRACT_ADDRESS: &'static str = "ERC721_CONTRACT_ADDRESS";
+    const ENVIRONMENT_CONTRACT_DEPLOYMENTS_FILE_PATH: &'static str =
+        "ERC721_CONTRACT_DEPLOYMENTS_FILE_PATH";
+    const ENVIRONMENT_CONTRACT_METADATA_FILE_PATH: &'static str =
+        "ERC721_CONTRACT_METADATA_FILE_PATH";
+    const ENVIRONMENT_ETHEREUM_HTTP_URL: &'static str = "ERC721_ETHEREUM_HTTP_URL";
+    const ENVIRONMENT_ETHEREUM_WEBSOCKET_URL: &'static str = "ERC721_ETHEREUM_WEBSOCKET_URL";
+    const ENVIRONMENT_REDIS_URL: &'static str = "ERC721_REDIS_URL";
+    const ENVIRONMENT_SERVER_SOCKET_ADDRESS: &'static str = "ERC721_SERVER_SOCKET_ADDRESS";
+    const ENVIRONMENT_STORAGE_BACKEND: &'static str = "ERC721_STORAGE_BACKEND";
+    const ENVIRONMENT_STORAGE_MODE: &'static str = "ERC721_STORAGE_MODE";
+    const ENVIRONMENT_STORAGE_SQL_URL: &'static str = "ERC721_STORAGE_SQL_URL";
+
+    // The TOML file is optional. The environment variables override its values.
+    pub fn load() -> Result<Configuration, Error> {
+        let mut configuration = match env::var(Self::ENVIRONMENT_CONFIGURATION_FILE_PATH) {
+            Ok(configuration_file_path) => Self::load_from_file(configuration_file_path.as_str())?,
+            Err(_) => Configuration::default(),
+        };
+
+        Self::override_from_environment(&mut configuration.redis.url, Self::ENVIRONMENT_REDIS_URL);
+        Self::override_from_environment(
+            &mut configuration.ethereum.websocket_url,
+            Self::ENVIRONMENT_ETHEREUM_WEBSOCKET_URL,
+        );
+        Self::override_from_environment(
+            &mut configuration.ethereum.http_url,
+            Self::ENVIRONMENT_ETHEREUM_HTTP_URL,
+        );
+        Self::override_from_environment(
+            &mut configuration.server.socket_address,
+            Self::ENVIRONMENT_SERVER_SOCKET_ADDRESS,
+        );
+        Self::override_from_environment(
+            &mut configuration.contract.metadata_file_path,
+            Self::ENVIRONMENT_CONTRACT_METADATA_FILE_PATH,
+        );
+        Self::override_from_environment(
+            &mut configuration.contract.address,
+            Self::ENVIRONMENT_CONTRACT_ADDRESS,
+        );
+        Self::override_from_environment(
+            &mut configuration.contract.deployments_file_path,
+            Self::ENVIRONMENT_CONTRACT_DEPLOYMENTS_FILE_PATH,
+        );
+
+        Self::override_from_environment(
+            &mut configuration.storage.sql_url,
+            Self::ENVIRONMENT_STORAGE_SQL_URL,
+        );
+
+        if let Ok(storage_backend) = env::var(Self::ENVIRONMENT_STORAGE_BACKEND) {
+            configuration.storage.backend = StorageBackend::from_str(storage_backend.as_str())?;
+        }
+
+        if let Ok(storage_mode) = env::var(Self::ENVIRONMENT_STORAGE_MODE) {
+            configuration.storage.mode = StorageMode::from_str(storage_mode.as_str())?;
+        }
+
+        Self::validate(&configuration)?;
+
+        return Ok(configuration);
+    }
+
+    fn load_from_file<'a>(configuration_file_path: &'a str) -> Result<Configuration, Error> {
+        let data = match fs::read_to_string(configuration_file_path) {
+            Ok(data_) => data_,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to read the configuration file '{}'",
+                        configuration_file_path
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        match toml::from_str::<Configuration>(data.as_str()) {
+            Ok(configuration) => {
+                return Ok(configuration);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to parse the configuration file '{}'",
+                        configuration_file_path
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    fn override_from_environment<'a>(value: &'a mut String, name: &'a str) -> () {
+        if let Ok(value_) = env::var(name) {
+            *value = value_;
+        }
+
+        return ();
+    }
+
+    fn validate<'a>(configuration: &'a Configuration) -> Result<(), Error> {
+        if let Err(error) = ConnectionInfo::from_str(configuration.redis.url.as_str()) {
+            return Err(Error::Config {
+                context: format!("Invalid 'redis.url' '{}'", configuration.redis.url),
+                source: Some(Box::from(error)),
+            });
+        }
+
+        Self::validate_url(
+            configuration.ethereum.websocket_url.as_str(),
+            "ethereum.websocket_url",
+            &["ws://", "wss://"],
+        )?;
+
+        Self::validate_url(
+            configuration.ethereum.http_url.as_str(),
+            "ethereum.http_url",
+            &["http://", "https://"],
+        )?;
+
+        // The host is resolved when the server is started.
+        let is_socket_address_valid = match configuration.server.socket_address.rsplit_once(':') {
+            Some((host, port)) => !host.is_empty() && u16::from_str(port).is_ok(),
+            None => false,
+        };
+
+        if !is_socket_address_valid {
+            return Err(Error::Config {
+                context: format!(
+                    "Invalid 'server.socket_address' '{}', expected 'host:port'",
+                    configuration.server.socket_address
+                ),
+                source: None,
+            });
+        }
+
+        if configuration.contract.metadata_file_path.is_empty() {
+            return Err(Error::Config {
+                context: "Invalid 'contract.metadata_file_path', the path is empty".to_string(),
+                source: None,
+            });
+        }
+
+        if configuration.contract.deployments_file_path.is_empty() {
+            return Err(Error::Config {
+                context: "Invalid 'contract.deployments_file_path', the path is empty".to_string(),
+                source: None,
+            });
+        }
+
+        if !configuration.contract.address.is_empty() {
+            if let Err(error) = Address::from_str(configuration.contract.address.as_str()) {
+                return Err(Error::Config {
+                    context: format!(
+                        "Invalid 'contract.address' '{}'",
+                        configuration.contract.address
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+
+        if configuration.storage.backend == StorageBackend::Sql {
+            Self::validate_url(
+                configuration.storage.sql_url.as_str(),
+                "storage.sql_url",
+                &["postgres://", "postgresql://", "sqlite:"],
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    fn validate_url<'a>(
+        url: &'a str,
+        name: &'a str,
+        scheme_registry: &'a [&'a str],
+    ) -> Result<(), Error> {
+        for scheme in scheme_registry.iter() {
+            if let Some(host) = url.strip_prefix(scheme) {
+                if !host.is_empty() {
+                    return Ok(());
+                }
+            }
+        }
+
+        return Err(Error::Config {
+            context: format!(
+                "Invalid '{}' '{}', expected the {} scheme and a host",
+                name,
+                url,
+                scheme_registry.join(" or ")
+            ),
+            source: None,
+        });
+    }
+}
+
 pub struct ConnectionPoolCreator;
 
 impl ConnectionPoolCreator {
-    const REDIS_URL: &'static str = "redis://redis";
-
-    pub async fn create(
+    pub async fn create<'a>(
+        redis_url: &'a str,
         connection_quantity: u32,
-    ) -> Result<Pool<RedisConnectionManager>, Box<dyn Error + 'static>> {
-        let connection_info = match ConnectionInfo::from_str(Self::REDIS_URL) {
+    ) -> Result<Pool<RedisConnectionManager>, Error> {
+        let connection_info = match ConnectionInfo::from_str(redis_url) {
             Ok(connection_info) => connection_info,
             Err(error) => {
-                return Err(Box::from(error));
+                return Err(Error::Redis {
+                    context: "Failed to parse the Redis URL".to_string(),
+                    source: error,
+                });
             }
         };
 
         let connection_manager = match RedisConnectionManager::new(connection_info) {
             Ok(connection_manager_) => connection_manager_,
             Err(error) => {
-                return Err(Box::from(error));
+                return Err(Error::Redis {
+                    context: "Failed to create the Redis connection manager".to_string(),
+                    source: error,
+                });
             }
         };
 
@@ -127,7 +575,10 @@ impl ConnectionPoolCreator {
         {
             Ok(connection_pool_) => connection_pool_,
             Err(error) => {
-                return Err(Box::from(error));
+                return Err(Error::Redis {
+                    context: "Failed to create the Redis connection pool".to_string(),
+                    source: error,
+                });
             }
         };
 
@@ -135,22 +586,221 @@ impl ConnectionPoolCreator {
     }
 }
 
-pub struct Web3ConnectionCreator;
+pub struct SqlConnectionPoolCreator;
 
-impl Web3ConnectionCreator {
-    const GANACHE_URL: &'static str = "ws://ganache:8545";
+impl SqlConnectionPoolCreator {
+    const MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+    // The embedded migrations are applied before the pool is returned.
+    pub async fn create<'a>(sql_url: &'a str, connection_quantity: u32) -> Result<AnyPool, Error> {
+        let connection_pool = match AnyPoolOptions::new()
+            .max_connections(connection_quantity)
+            .connect(sql_url)
+            .await
+        {
+            Ok(connection_pool_) => connection_pool_,
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to create the SQL connection pool".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        if let Err(error) = Self::MIGRATOR.run(&connection_pool).await {
+            return Err(Error::Sql {
+                context: "Failed to apply the SQL migrations".to_string(),
+                source: SqlxError::from(error),
+            });
+        }
+
+        return Ok(connection_pool);
+    }
+}
+
+pub struct EventChannelSubscriptionCreator;
+
+impl EventChannelSubscriptionCreator {
+    // A subscribed connection can not run other commands, so it is not taken from the pool.
+    pub async fn create<'a>(redis_url: &'a str) -> Result<PubSub, Error> {
+        let client = match Client::open(redis_url) {
+            Ok(client_) => client_,
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to parse the Redis URL".to_string(),
+                    source: error,
+                });
+            }
+        };
 
-    pub async fn create_websocket_connection() -> Result<Web3<WebSocket>, Box<dyn Error + 'static>>
+        let connection = match client.get_async_connection().await {
+            Ok(connection_) => connection_,
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to connect to Redis".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        let mut pubsub = connection.into_pubsub();
+
+        if let Err(error) = pubsub.subscribe(StorageKeyManager::EVENT_CHANNEL).await {
+            return Err(Error::Redis {
+                context: "Failed to subscribe for the event channel".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(pubsub);
+    }
+}
+
+pub enum ConnectionState {
+    Connecting { attempt: u32 },
+    Connected,
+    Subscribed,
+    Disconnected { reason: String },
+    WaitingForReconnection { delay: Duration },
+}
+
+impl Display for ConnectionState {
+    fn fmt<'a>(&'a self, formatter: &'a mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Connecting { attempt } => {
+                write!(formatter, "Connecting, attempt {}.", attempt)
+            }
+            Self::Connected => write!(formatter, "Connected."),
+            Self::Subscribed => write!(formatter, "Subscribed."),
+            Self::Disconnected { ref reason } => {
+                write!(formatter, "Disconnected: {}", reason)
+            }
+            Self::WaitingForReconnection { delay } => {
+                write!(formatter, "Reconnecting in {} ms.", delay.as_millis())
+            }
+        }
+    }
+}
+
+pub struct ReconnectionBackoff {
+    attempt: u32,
+}
+
+impl ReconnectionBackoff {
+    const INITIAL_DELAY_MILLISECONDS: u64 = 500;
+    const MAXIMUM_DELAY_MILLISECONDS: u64 = 30000;
+    const MAXIMUM_EXPONENT: u32 = 16;
+
+    pub fn new() -> Self {
+        return Self { attempt: 0 };
+    }
+
+    pub fn get_attempt(&self) -> u32 {
+        return self.attempt;
+    }
+
+    // Half of the delay is fixed and half is random, so that the delay still grows with
+    // each attempt, but reconnecting processes do not hit the node at the same moment.
+    pub fn next_delay(&mut self) -> Duration {
+        let exponent = std::cmp::min(self.attempt, Self::MAXIMUM_EXPONENT);
+
+        let delay_ceiling = std::cmp::min(
+            Self::INITIAL_DELAY_MILLISECONDS.saturating_mul(1 << exponent),
+            Self::MAXIMUM_DELAY_MILLISECONDS,
+        );
+
+        self.attempt = self.attempt.saturating_add(1);
+
+        let delay_half = delay_ceiling / 2;
+
+        return Duration::from_millis(
+            delay_half + rand::thread_rng().gen_range(0..=(delay_ceiling - delay_half)),
+        );
+    }
+
+    pub fn reset(&mut self) -> () {
+        self.attempt = 0;
+
+        return ();
+    }
+
+    pub async fn wait<'a, F>(&'a mut self, on_connection_state_change: &'a F) -> ()
+    where
+        F: Fn(&ConnectionState),
     {
-        let transport = match WebSocket::new(Self::GANACHE_URL).await {
+        let delay = self.next_delay();
+
+        on_connection_state_change(&ConnectionState::WaitingForReconnection { delay });
+
+        tokio::time::sleep(delay).await;
+
+        return ();
+    }
+}
+
+pub struct Web3ConnectionCreator;
+
+impl Web3ConnectionCreator {
+    pub async fn create_websocket_connection<'a>(
+        websocket_url: &'a str,
+    ) -> Result<Web3<WebSocket>, Error> {
+        let transport = match WebSocket::new(websocket_url).await {
             Ok(transport_) => transport_,
             Err(error) => {
-                return Err(Box::from(error));
+                return Err(Error::Transport {
+                    context: "Failed to connect to the Ethereum node".to_string(),
+                    source: error,
+                });
             }
         };
 
         return Ok(Web3::new(transport));
     }
+
+    // Retries until the connection is established.
+    pub async fn create_websocket_connection_supervised<'a, F>(
+        websocket_url: &'a str,
+        reconnection_backoff: &'a mut ReconnectionBackoff,
+        on_connection_state_change: &'a F,
+    ) -> Web3<WebSocket>
+    where
+        F: Fn(&ConnectionState),
+    {
+        loop {
+            on_connection_state_change(&ConnectionState::Connecting {
+                attempt: reconnection_backoff.get_attempt() + 1,
+            });
+
+            match Self::create_websocket_connection(websocket_url).await {
+                Ok(web3) => {
+                    on_connection_state_change(&ConnectionState::Connected);
+
+                    return web3;
+                }
+                Err(error) => {
+                    on_connection_state_change(&ConnectionState::Disconnected {
+                        reason: error.get_report(),
+                    });
+                }
+            }
+
+            reconnection_backoff.wait(on_connection_state_change).await;
+        }
+    }
+}
+
+pub struct ContractFilterCreator;
+
+impl ContractFilterCreator {
+    // A log matches the filter if it is emitted by any of the contracts and its topic0 is any of the topics.
+    pub fn create<'a>(
+        contract_address_registry: &'a [Address],
+        event_topic_registry: &'a [H256],
+    ) -> FilterBuilder {
+        return FilterBuilder::default()
+            .address(contract_address_registry.to_vec())
+            .topics(Some(event_topic_registry.to_vec()), None, None, None);
+    }
 }
 
 pub struct ContractSubscriptionCreator;
@@ -158,26 +808,39 @@ pub struct ContractSubscriptionCreator;
 impl ContractSubscriptionCreator {
     pub async fn create<'a>(
         web3: &'a Web3<WebSocket>,
-        contract_address: Address,
-        event_keccak_256_hash: &'a str,
-    ) -> Result<SubscriptionStream<WebSocket, Log>, Box<dyn Error + 'static>> {
-        let mut bytes: [u8; 32] = [0; 32];
+        contract_address_registry: &'a [Address],
+        event_topic_registry: &'a [H256],
+    ) -> Result<SubscriptionStream<WebSocket, Log>, Error> {
+        let filter =
+            ContractFilterCreator::create(contract_address_registry, event_topic_registry).build();
 
-        if let Err(error) = hex::decode_to_slice(event_keccak_256_hash, bytes.as_mut_slice()) {
-            return Err(Box::from(error));
+        let subscription_stream = match web3.eth_subscribe().subscribe_logs(filter).await {
+            Ok(subscription_stream_) => subscription_stream_,
+            Err(error) => {
+                return Err(Error::Transport {
+                    context: "Failed to subscribe for logs".to_string(),
+                    source: error,
+                });
+            }
         };
 
-        let hash = H256::from(bytes);
+        return Ok(subscription_stream);
+    }
+}
 
-        let filter = FilterBuilder::default()
-            .address(vec![contract_address])
-            .topics(Some(vec![hash]), None, None, None)
-            .build();
+pub struct BlockHeaderSubscriptionCreator;
 
-        let subscription_stream = match web3.eth_subscribe().subscribe_logs(filter).await {
+impl BlockHeaderSubscriptionCreator {
+    pub async fn create<'a>(
+        web3: &'a Web3<WebSocket>,
+    ) -> Result<SubscriptionStream<WebSocket, BlockHeader>, Error> {
+        let subscription_stream = match web3.eth_subscribe().subscribe_new_heads().await {
             Ok(subscription_stream_) => subscription_stream_,
             Err(error) => {
-                return Err(Box::from(error));
+                return Err(Error::Transport {
+                    context: "Failed to subscribe for block headers".to_string(),
+                    source: error,
+                });
             }
         };
 
@@ -185,56 +848,5801 @@ impl ContractSubscriptionCreator {
     }
 }
 
-pub struct ResponseCreator;
-
-impl ResponseCreator {
-    fn create(status_code: StatusCode, data: Option<String>) -> Response<Body> {
-        let mut header_map = HeaderMap::new();
-        header_map.append(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("application/json"),
-        );
+pub struct ContractLogLoader;
 
-        let mut parts = Response::new(()).into_parts().0;
-        parts.status = status_code;
-        parts.version = Version::HTTP_2;
-        parts.headers = header_map;
+impl ContractLogLoader {
+    pub async fn load<'a>(
+        web3: &'a Web3<WebSocket>,
+        contract_address_registry: &'a [Address],
+        event_topic_registry: &'a [H256],
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<Log>, Error> {
+        let filter = ContractFilterCreator::create(contract_address_registry, event_topic_registry)
+            .from_block(BlockNumber::Number(U64::from(from_block)))
+            .to_block(BlockNumber::Number(U64::from(to_block)))
+            .build();
 
-        let body = match data {
-            Some(data_) => Body::from(data_),
-            None => Body::empty(),
+        let log_registry = match web3.eth().logs(filter).await {
+            Ok(log_registry_) => log_registry_,
+            Err(error) => {
+                return Err(Error::Transport {
+                    context: "Failed to load logs".to_string(),
+                    source: error,
+                });
+            }
         };
 
-        return Response::from_parts(parts, body);
+        return Ok(log_registry);
     }
+}
 
-    pub fn create_ok(data: Option<String>) -> Response<Body> {
-        return Self::create(StatusCode::OK, data);
+#[derive(Serialize, Deserialize)]
+pub struct CollectionCreatedEvent {
+    pub collection: Address,
+    pub name: String,
+    pub symbol: String,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TokenMintedEvent {
+    pub collection: Address,
+    pub recipient: Address,
+    pub token_id: U256,
+    pub token_uri: String,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TransferEvent {
+    pub collection: Address,
+    pub from: Address,
+    pub to: Address,
+    pub token_id: U256,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+// The owner is the zero address after the token is burned.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TokenOwnership {
+    pub collection: Address,
+    pub token_id: U256,
+    pub owner: Address,
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OwnedToken {
+    pub collection: Address,
+    pub token_id: U256,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ApprovalEvent {
+    pub collection: Address,
+    pub owner: Address,
+    pub approved: Address,
+    pub token_id: U256,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ApprovalForAllEvent {
+    pub collection: Address,
+    pub owner: Address,
+    pub operator: Address,
+    pub approved: bool,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    CollectionCreated,
+    TokenMinted,
+    Transfer,
+    Approval,
+    ApprovalForAll,
+}
+
+impl EventType {
+    pub const REGISTRY: [Self; 5] = [
+        Self::CollectionCreated,
+        Self::TokenMinted,
+        Self::Transfer,
+        Self::Approval,
+        Self::ApprovalForAll,
+    ];
+
+    // The name is the type of the event in the API of server_runner.
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Self::CollectionCreated => {
+                return "collection_created";
+            }
+            Self::TokenMinted => {
+                return "token_minted";
+            }
+            Self::Transfer => {
+                return "transfer";
+            }
+            Self::Approval => {
+                return "approval";
+            }
+            Self::ApprovalForAll => {
+                return "approval_for_all";
+            }
+        }
     }
 
-    pub fn create_bad_request() -> Response<Body> {
-        return Self::create(StatusCode::BAD_REQUEST, None);
+    pub fn from_name<'a>(name: &'a str) -> Option<Self> {
+        return Self::REGISTRY
+            .into_iter()
+            .find(|event_type| event_type.get_name() == name);
     }
 
-    pub fn create_not_found() -> Response<Body> {
-        return Self::create(StatusCode::NOT_FOUND, None);
+    // The name of the event in the contract ABI.
+    pub fn get_event_name(&self) -> &'static str {
+        match *self {
+            Self::CollectionCreated => {
+                return "CollectionCreated";
+            }
+            Self::TokenMinted => {
+                return "TokenMinted";
+            }
+            Self::Transfer => {
+                return "Transfer";
+            }
+            Self::Approval => {
+                return "Approval";
+            }
+            Self::ApprovalForAll => {
+                return "ApprovalForAll";
+            }
+        }
     }
 
-    pub fn create_internal_server_error() -> Response<Body> {
-        return Self::create(StatusCode::INTERNAL_SERVER_ERROR, None);
+    // The ABI of the contract that emits the event, which the event is decoded with.
+    pub fn get_contract_abi(&self) -> Result<&'static Contract, Error> {
+        match *self {
+            Self::CollectionCreated | Self::TokenMinted => {
+                return ContractAbiLoader::load_collection_aggregator();
+            }
+            Self::Transfer | Self::Approval | Self::ApprovalForAll => {
+                return ContractAbiLoader::load_collection();
+            }
+        }
     }
-}
 
-pub struct StorageKeyManager;
+    pub fn get_topic(&self) -> Result<H256, Error> {
+        return EventTopicCreator::create_from_abi(self.get_contract_abi()?, self.get_event_name());
+    }
 
-impl StorageKeyManager {
-    pub const EVENT_COLLECTION_CREATED_PART: &'static str = "cc";
-    pub const EVENT_COLLECTION_CREATED_QUANTITY: &'static str = "cc:q";
-    pub const EVENT_TOKEN_MINTED_PART: &'static str = "tm";
-    pub const EVENT_TOKEN_MINTED_QUANTITY: &'static str = "tm:q";
-    const SEPARATOR: &'static str = ":";
+    // The event of any ABI is stored as the type that has its topic0, which is decoded the same way.
+    pub fn from_topic(topic: H256) -> Result<Option<Self>, Error> {
+        for event_type in Self::REGISTRY.into_iter() {
+            if event_type.get_topic()? == topic {
+                return Ok(Some(event_type));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    // The fields of the event that are indexed besides its position.
+    pub fn get_index_field_registry(&self) -> &'static [&'static str] {
+        match *self {
+            Self::CollectionCreated => {
+                return &["collection"];
+            }
+            Self::TokenMinted => {
+                return &["collection", "recipient"];
+            }
+            Self::Transfer | Self::Approval | Self::ApprovalForAll => {
+                return &[];
+            }
+        }
+    }
+}
+
+// Selects the events that have the address in the field.
+#[derive(Clone, Copy)]
+pub enum EventIndex {
+    Collection(Address),
+    Recipient(Address),
+}
+
+impl EventIndex {
+    pub fn get_field_name(&self) -> &'static str {
+        match *self {
+            Self::Collection(_) => {
+                return "collection";
+            }
+            Self::Recipient(_) => {
+                return "recipient";
+            }
+        }
+    }
+
+    pub fn get_address(&self) -> Address {
+        match *self {
+            Self::Collection(address) | Self::Recipient(address) => {
+                return address;
+            }
+        }
+    }
+
+    pub fn is_kept_for(&self, event_type: EventType) -> bool {
+        return event_type
+            .get_index_field_registry()
+            .contains(&self.get_field_name());
+    }
+}
+
+// The removed event is retracted by a reorganization and is kept only to hold its index.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    Pending,
+    Final,
+    Removed,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EventRecord<T> {
+    pub status: EventStatus,
+    pub event: T,
+}
+
+pub struct EventStatusUpdater;
+
+impl EventStatusUpdater {
+    pub fn read<'a>(data: &'a str) -> Result<EventStatus, Error> {
+        match serde_json::from_str::<EventRecord<IgnoredAny>>(data) {
+            Ok(event_record) => {
+                return Ok(event_record.status);
+            }
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: "Failed to parse the stored event".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    // The stored record is decoded and encoded again with the status.
+    pub fn update<'a>(data: &'a str, status: EventStatus) -> Result<String, Error> {
+        let mut event_record = match serde_json::from_str::<EventRecord<Value>>(data) {
+            Ok(event_record_) => event_record_,
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: "Failed to parse the stored event".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        event_record.status = status;
+
+        match serde_json::to_string(&event_record) {
+            Ok(data_) => {
+                return Ok(data_);
+            }
+            Err(error) => {
+                return Err(Error::Serialization {
+                    context: "Failed to serialize the event record".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+}
+
+// The confirmed event has the final status and the retracted one the removed status in the notification.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventChange {
+    Appended,
+    Confirmed,
+    Retracted,
+}
+
+impl EventChange {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Self::Appended => {
+                return "appended";
+            }
+            Self::Confirmed => {
+                return "confirmed";
+            }
+            Self::Retracted => {
+                return "retracted";
+            }
+        }
+    }
+
+    pub fn from_name<'a>(name: &'a str) -> Option<Self> {
+        match name {
+            "appended" => {
+                return Some(Self::Appended);
+            }
+            "confirmed" => {
+                return Some(Self::Confirmed);
+            }
+            "retracted" => {
+                return Some(Self::Retracted);
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventNotification {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub change: EventChange,
+    pub cursor: String,
+    pub record: Value,
+}
+
+// The page starts after the zero-based offset or after the event of the cursor.
+#[derive(Clone)]
+pub enum EventPageStart {
+    Offset(i64),
+    After(String),
+}
+
+#[derive(Clone)]
+pub struct EventEntry {
+    pub cursor: String,
+    pub data: String,
+}
+
+// The cursor is the one of the last event of the page, if there are events after it.
+#[derive(Serialize, Deserialize)]
+pub struct EventPage<T> {
+    pub events: Vec<EventRecord<T>>,
+    pub next_cursor: Option<String>,
+    pub total: i64,
+}
+
+// The block number is null if the collection is not in the index yet and is read from the contract.
+#[derive(Serialize, Deserialize)]
+pub struct CollectionSummary {
+    pub collection: Address,
+    pub name: String,
+    pub symbol: String,
+    pub block_number: Option<u64>,
+    pub mint_quantity: i64,
+    pub is_indexed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionPage {
+    pub collections: Vec<CollectionSummary>,
+    pub next_cursor: Option<String>,
+    pub total: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventCheckpoint {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+impl EventCheckpoint {
+    pub fn from_log<'a>(log: &'a Log) -> Result<Self, Error> {
+        return Ok(Self {
+            block_number: ContractEventDecoder::get_block_number(log)?,
+            log_index: ContractEventDecoder::get_log_index(log)?,
+        });
+    }
+
+    // The indexed events are addressed by their checkpoints, like '5:1'.
+    pub fn format_cursor(&self) -> String {
+        return format!("{}:{}", self.block_number, self.log_index);
+    }
+
+    pub fn parse_cursor<'a>(cursor: &'a str) -> Option<Self> {
+        let (block_number, log_index) = cursor.split_once(':')?;
+
+        return Some(Self {
+            block_number: u64::from_str(block_number).ok()?,
+            log_index: u64::from_str(log_index).ok()?,
+        });
+    }
+
+    // The greatest checkpoint that is still before this one. Nothing is before the first log of the chain.
+    pub fn get_previous(&self) -> Option<Self> {
+        if self.log_index > 0 {
+            return Some(Self {
+                block_number: self.block_number,
+                log_index: self.log_index - 1,
+            });
+        }
+
+        if self.block_number > 0 {
+            return Some(Self {
+                block_number: self.block_number - 1,
+                log_index: u64::MAX,
+            });
+        }
+
+        return None;
+    }
+}
+
+static COLLECTION_AGGREGATOR_ABI: OnceLock<Contract> = OnceLock::new();
+static COLLECTION_ABI: OnceLock<Contract> = OnceLock::new();
+
+pub struct ContractAbiLoader;
+
+impl ContractAbiLoader {
+    const COLLECTION_AGGREGATOR_METADATA: &'static str =
+        include_str!("./../../contract/build/contracts/CollectionAggregator.json");
+    const COLLECTION_METADATA: &'static str =
+        include_str!("./../../contract/build/contracts/Collection.json");
+
+    pub fn load_collection_aggregator() -> Result<&'static Contract, Error> {
+        return Self::load_once(
+            &COLLECTION_AGGREGATOR_ABI,
+            Self::COLLECTION_AGGREGATOR_METADATA,
+        );
+    }
+
+    pub fn load_collection() -> Result<&'static Contract, Error> {
+        return Self::load_once(&COLLECTION_ABI, Self::COLLECTION_METADATA);
+    }
+
+    // The artifact is the JSON file built by Truffle, like 'source/contract/build/contracts/Collection.json'.
+    pub fn load_from_artifact<'a>(artifact_path: &'a Path) -> Result<Contract, Error> {
+        let contract_metadata = match fs::read_to_string(artifact_path) {
+            Ok(contract_metadata_) => contract_metadata_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!("Failed to read the artifact '{}'", artifact_path.display()),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        return Self::load(contract_metadata.as_str());
+    }
+
+    // The embedded metadata is parsed only once, by the first call that succeeds.
+    fn load_once(
+        contract_abi: &'static OnceLock<Contract>,
+        contract_metadata: &'static str,
+    ) -> Result<&'static Contract, Error> {
+        if let Some(contract) = contract_abi.get() {
+            return Ok(contract);
+        }
+
+        let contract = Self::load(contract_metadata)?;
+
+        return Ok(contract_abi.get_or_init(|| contract));
+    }
+
+    fn load<'a>(contract_metadata: &'a str) -> Result<Contract, Error> {
+        let json_value = match serde_json::from_str::<Value>(contract_metadata) {
+            Ok(json_value_) => json_value_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: "Failed to parse the contract metadata".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let json_value_abi = match json_value.get("abi") {
+            Some(json_value_abi_) => json_value_abi_,
+            None => {
+                return Err(Error::Abi {
+                    context: "The contract metadata has no ABI".to_string(),
+                    source: None,
+                });
+            }
+        };
+
+        let contract = match serde_json::from_value::<Contract>(json_value_abi.clone()) {
+            Ok(contract_) => contract_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: "Failed to parse the contract ABI".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        return Ok(contract);
+    }
+}
+
+pub struct ContractBytecodeLoader;
+
+impl ContractBytecodeLoader {
+    // The bytecode is the creation code of the contract in the 'bytecode' field of the artifact.
+    pub fn load_from_artifact<'a>(artifact_path: &'a Path) -> Result<Vec<u8>, Error> {
+        let contract_metadata = match fs::read_to_string(artifact_path) {
+            Ok(contract_metadata_) => contract_metadata_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!("Failed to read the artifact '{}'", artifact_path.display()),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let json_value = match serde_json::from_str::<Value>(contract_metadata.as_str()) {
+            Ok(json_value_) => json_value_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: "Failed to parse the contract metadata".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let json_value_bytecode = match json_value.get("bytecode") {
+            Some(json_value_bytecode_) => json_value_bytecode_,
+            None => {
+                return Err(Error::Abi {
+                    context: "The contract metadata has no bytecode".to_string(),
+                    source: None,
+                });
+            }
+        };
+
+        let bytecode = match serde_json::from_value::<Bytes>(json_value_bytecode.clone()) {
+            Ok(bytecode_) => bytecode_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: "Failed to parse the contract bytecode".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        if bytecode.0.is_empty() {
+            return Err(Error::Abi {
+                context: "The contract bytecode is empty".to_string(),
+                source: None,
+            });
+        }
+
+        return Ok(bytecode.0);
+    }
+}
+
+pub struct EventTopicCreator;
+
+impl EventTopicCreator {
+    // The signature is the event name with the parameter types, like 'Transfer(address,address,uint256)'.
+    pub fn create_from_signature<'a>(event_signature: &'a str) -> H256 {
+        return H256::from(keccak256(event_signature.as_bytes()));
+    }
+
+    pub fn create_from_abi<'a>(contract: &'a Contract, event_name: &'a str) -> Result<H256, Error> {
+        match contract.event(event_name) {
+            Ok(event) => {
+                return Ok(event.signature());
+            }
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!(
+                        "Failed to find the '{}' event in the contract ABI",
+                        event_name
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    pub fn create_from_artifact<'a>(
+        artifact_path: &'a Path,
+        event_name: &'a str,
+    ) -> Result<H256, Error> {
+        return Self::create_from_abi(
+            &ContractAbiLoader::load_from_artifact(artifact_path)?,
+            event_name,
+        );
+    }
+}
+
+pub struct ContractEventDecoder;
+
+impl ContractEventDecoder {
+    const EVENT_APPROVAL_FOR_ALL_NAME: &'static str = "ApprovalForAll";
+    const EVENT_APPROVAL_NAME: &'static str = "Approval";
+    const EVENT_COLLECTION_CREATED_NAME: &'static str = "CollectionCreated";
+    const EVENT_TOKEN_MINTED_NAME: &'static str = "TokenMinted";
+    const EVENT_TRANSFER_NAME: &'static str = "Transfer";
+
+    pub fn decode_collection_created<'a>(log: &'a Log) -> Result<CollectionCreatedEvent, Error> {
+        let log_param_registry = Self::decode(
+            log,
+            ContractAbiLoader::load_collection_aggregator()?,
+            Self::EVENT_COLLECTION_CREATED_NAME,
+        )?;
+
+        return Ok(CollectionCreatedEvent {
+            collection: Self::get_address(&log_param_registry, "collection")?,
+            name: Self::get_string(&log_param_registry, "name")?,
+            symbol: Self::get_string(&log_param_registry, "symbol")?,
+            block_number: Self::get_block_number(log)?,
+            tx_hash: Self::get_transaction_hash(log)?,
+            log_index: Self::get_log_index(log)?,
+        });
+    }
+
+    pub fn decode_token_minted<'a>(log: &'a Log) -> Result<TokenMintedEvent, Error> {
+        let log_param_registry = Self::decode(
+            log,
+            ContractAbiLoader::load_collection_aggregator()?,
+            Self::EVENT_TOKEN_MINTED_NAME,
+        )?;
+
+        return Ok(TokenMintedEvent {
+            collection: Self::get_address(&log_param_registry, "collection")?,
+            recipient: Self::get_address(&log_param_registry, "recipient")?,
+            token_id: Self::get_uint(&log_param_registry, "tokenId")?,
+            token_uri: Self::get_string(&log_param_registry, "tokenUri")?,
+            block_number: Self::get_block_number(log)?,
+            tx_hash: Self::get_transaction_hash(log)?,
+            log_index: Self::get_log_index(log)?,
+        });
+    }
+
+    pub fn decode_transfer<'a>(log: &'a Log) -> Result<TransferEvent, Error> {
+        let log_param_registry = Self::decode(
+            log,
+            ContractAbiLoader::load_collection()?,
+            Self::EVENT_TRANSFER_NAME,
+        )?;
+
+        return Ok(TransferEvent {
+            collection: log.address,
+            from: Self::get_address(&log_param_registry, "from")?,
+            to: Self::get_address(&log_param_registry, "to")?,
+            token_id: Self::get_uint(&log_param_registry, "tokenId")?,
+            block_number: Self::get_block_number(log)?,
+            tx_hash: Self::get_transaction_hash(log)?,
+            log_index: Self::get_log_index(log)?,
+        });
+    }
+
+    pub fn decode_approval<'a>(log: &'a Log) -> Result<ApprovalEvent, Error> {
+        let log_param_registry = Self::decode(
+            log,
+            ContractAbiLoader::load_collection()?,
+            Self::EVENT_APPROVAL_NAME,
+        )?;
+
+        return Ok(ApprovalEvent {
+            collection: log.address,
+            owner: Self::get_address(&log_param_registry, "owner")?,
+            approved: Self::get_address(&log_param_registry, "approved")?,
+            token_id: Self::get_uint(&log_param_registry, "tokenId")?,
+            block_number: Self::get_block_number(log)?,
+            tx_hash: Self::get_transaction_hash(log)?,
+            log_index: Self::get_log_index(log)?,
+        });
+    }
+
+    pub fn decode_approval_for_all<'a>(log: &'a Log) -> Result<ApprovalForAllEvent, Error> {
+        let log_param_registry = Self::decode(
+            log,
+            ContractAbiLoader::load_collection()?,
+            Self::EVENT_APPROVAL_FOR_ALL_NAME,
+        )?;
+
+        return Ok(ApprovalForAllEvent {
+            collection: log.address,
+            owner: Self::get_address(&log_param_registry, "owner")?,
+            operator: Self::get_address(&log_param_registry, "operator")?,
+            approved: Self::get_bool(&log_param_registry, "approved")?,
+            block_number: Self::get_block_number(log)?,
+            tx_hash: Self::get_transaction_hash(log)?,
+            log_index: Self::get_log_index(log)?,
+        });
+    }
+
+    fn decode<'a>(
+        log: &'a Log,
+        contract: &'a Contract,
+        event_name: &'a str,
+    ) -> Result<Vec<LogParam>, Error> {
+        let event = match contract.event(event_name) {
+            Ok(event_) => event_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!(
+                        "Failed to find the '{}' event in the contract ABI",
+                        event_name
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let raw_log = RawLog {
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        };
+
+        let log_ = match event.parse_log(raw_log) {
+            Ok(log__) => log__,
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: format!("Failed to decode the '{}' event log", event_name),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        return Ok(log_.params);
+    }
+
+    fn get_token<'a>(
+        log_param_registry: &'a [LogParam],
+        name: &'a str,
+    ) -> Result<&'a Token, Error> {
+        for log_param in log_param_registry.iter() {
+            if log_param.name == name {
+                return Ok(&log_param.value);
+            }
+        }
+
+        return Err(Error::Decoding {
+            context: format!("The '{}' log parameter is missing", name),
+            source: None,
+        });
+    }
+
+    fn get_address<'a>(
+        log_param_registry: &'a [LogParam],
+        name: &'a str,
+    ) -> Result<Address, Error> {
+        match Self::get_token(log_param_registry, name)? {
+            Token::Address(address) => {
+                return Ok(*address);
+            }
+            _ => {
+                return Err(Error::Decoding {
+                    context: format!("The '{}' log parameter is not an address", name),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    fn get_string<'a>(log_param_registry: &'a [LogParam], name: &'a str) -> Result<String, Error> {
+        match Self::get_token(log_param_registry, name)? {
+            Token::String(string) => {
+                return Ok(string.clone());
+            }
+            _ => {
+                return Err(Error::Decoding {
+                    context: format!("The '{}' log parameter is not a string", name),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    fn get_uint<'a>(log_param_registry: &'a [LogParam], name: &'a str) -> Result<U256, Error> {
+        match Self::get_token(log_param_registry, name)? {
+            Token::Uint(uint) => {
+                return Ok(*uint);
+            }
+            _ => {
+                return Err(Error::Decoding {
+                    context: format!("The '{}' log parameter is not an unsigned integer", name),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    fn get_bool<'a>(log_param_registry: &'a [LogParam], name: &'a str) -> Result<bool, Error> {
+        match Self::get_token(log_param_registry, name)? {
+            Token::Bool(bool_) => {
+                return Ok(*bool_);
+            }
+            _ => {
+                return Err(Error::Decoding {
+                    context: format!("The '{}' log parameter is not a boolean", name),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    fn get_block_number<'a>(log: &'a Log) -> Result<u64, Error> {
+        match log.block_number {
+            Some(block_number) => {
+                return Ok(block_number.as_u64());
+            }
+            None => {
+                return Err(Error::Decoding {
+                    context: "The log has no block number".to_string(),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    pub fn get_transaction_hash<'a>(log: &'a Log) -> Result<H256, Error> {
+        match log.transaction_hash {
+            Some(transaction_hash) => {
+                return Ok(transaction_hash);
+            }
+            None => {
+                return Err(Error::Decoding {
+                    context: "The log has no transaction hash".to_string(),
+                    source: None,
+                });
+            }
+        }
+    }
+
+    fn get_log_index<'a>(log: &'a Log) -> Result<u64, Error> {
+        match log.log_index {
+            Some(log_index) => {
+                return Ok(log_index.as_u64());
+            }
+            None => {
+                return Err(Error::Decoding {
+                    context: "The log has no log index".to_string(),
+                    source: None,
+                });
+            }
+        }
+    }
+}
+
+pub struct CollectionRegistryReader;
+
+impl CollectionRegistryReader {
+    pub async fn read<'a, T>(
+        web3: &'a Web3<T>,
+        collection_aggregator_address: Address,
+    ) -> Result<Vec<Address>, Error>
+    where
+        T: Transport,
+    {
+        let length = Self::read_length(web3, collection_aggregator_address).await?;
+
+        let mut collection_registry: Vec<Address> = vec![];
+
+        let mut index = U256::zero();
+
+        while index < length {
+            collection_registry
+                .push(Self::read_by_index(web3, collection_aggregator_address, index).await?);
+
+            index = index + 1;
+        }
+
+        return Ok(collection_registry);
+    }
+
+    pub async fn read_length<'a, T>(
+        web3: &'a Web3<T>,
+        collection_aggregator_address: Address,
+    ) -> Result<U256, Error>
+    where
+        T: Transport,
+    {
+        let contract = Web3Contract::new(
+            web3.eth(),
+            collection_aggregator_address,
+            ContractAbiLoader::load_collection_aggregator()?.clone(),
+        );
+
+        match contract
+            .query::<U256, _, _, _>(
+                "collectionRegistryGetLength",
+                (),
+                None,
+                Options::default(),
+                None,
+            )
+            .await
+        {
+            Ok(length) => {
+                return Ok(length);
+            }
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    "Failed to read the collection registry",
+                    error,
+                ));
+            }
+        }
+    }
+
+    // The index is zero-based and less than the length of the registry.
+    pub async fn read_by_index<'a, T>(
+        web3: &'a Web3<T>,
+        collection_aggregator_address: Address,
+        index: U256,
+    ) -> Result<Address, Error>
+    where
+        T: Transport,
+    {
+        let contract = Web3Contract::new(
+            web3.eth(),
+            collection_aggregator_address,
+            ContractAbiLoader::load_collection_aggregator()?.clone(),
+        );
+
+        match contract
+            .query::<Address, _, _, _>(
+                "collectionRegistryGetByIndex",
+                (index,),
+                None,
+                Options::default(),
+                None,
+            )
+            .await
+        {
+            Ok(collection) => {
+                return Ok(collection);
+            }
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    "Failed to read the collection registry",
+                    error,
+                ));
+            }
+        }
+    }
+
+    pub async fn is_exist<'a, T>(
+        web3: &'a Web3<T>,
+        collection_aggregator_address: Address,
+        collection: Address,
+    ) -> Result<bool, Error>
+    where
+        T: Transport,
+    {
+        let contract = Web3Contract::new(
+            web3.eth(),
+            collection_aggregator_address,
+            ContractAbiLoader::load_collection_aggregator()?.clone(),
+        );
+
+        match contract
+            .query::<bool, _, _, _>(
+                "collectionRegistryIsExist",
+                (collection,),
+                None,
+                Options::default(),
+                None,
+            )
+            .await
+        {
+            Ok(is_exist) => {
+                return Ok(is_exist);
+            }
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    "Failed to read the collection registry",
+                    error,
+                ));
+            }
+        }
+    }
+}
+
+pub struct CollectionMetadataReader;
+
+impl CollectionMetadataReader {
+    // Returns the name and the symbol of the collection.
+    pub async fn read<'a, T>(
+        web3: &'a Web3<T>,
+        collection: Address,
+    ) -> Result<(String, String), Error>
+    where
+        T: Transport,
+    {
+        let contract = Web3Contract::new(
+            web3.eth(),
+            collection,
+            ContractAbiLoader::load_collection()?.clone(),
+        );
+
+        let name = match contract
+            .query::<String, _, _, _>("name", (), None, Options::default(), None)
+            .await
+        {
+            Ok(name_) => name_,
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    "Failed to read the collection name",
+                    error,
+                ));
+            }
+        };
+
+        let symbol = match contract
+            .query::<String, _, _, _>("symbol", (), None, Options::default(), None)
+            .await
+        {
+            Ok(symbol_) => symbol_,
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    "Failed to read the collection symbol",
+                    error,
+                ));
+            }
+        };
+
+        return Ok((name, symbol));
+    }
+}
+
+pub struct ResponseCreator;
+
+impl ResponseCreator {
+    fn create(status_code: StatusCode, data: Option<String>) -> Response<Body> {
+        let mut header_map = HeaderMap::new();
+        header_map.append(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+
+        let mut parts = Response::new(()).into_parts().0;
+        parts.status = status_code;
+        parts.version = Version::HTTP_2;
+        parts.headers = header_map;
+
+        let body = match data {
+            Some(data_) => Body::from(data_),
+            None => Body::empty(),
+        };
+
+        return Response::from_parts(parts, body);
+    }
+
+    pub fn create_event_stream(body: Body) -> Response<Body> {
+        let mut header_map = HeaderMap::new();
+        header_map.append(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        header_map.append(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+        let mut parts = Response::new(()).into_parts().0;
+        parts.status = StatusCode::OK;
+        parts.headers = header_map;
+
+        return Response::from_parts(parts, body);
+    }
+
+    pub fn create_ok(data: Option<String>) -> Response<Body> {
+        return Self::create(StatusCode::OK, data);
+    }
+
+    // The error body is '{"code": _, "message": _, "details": _}', where the details are null or an object.
+    fn create_error<'a>(
+        status_code: StatusCode,
+        code: &'a str,
+        message: &'a str,
+        details: Option<Value>,
+    ) -> Response<Body> {
+        let data = json!({
+            "code": code,
+            "message": message,
+            "details": details.unwrap_or(Value::Null),
+        });
+
+        return Self::create(status_code, Some(data.to_string()));
+    }
+
+    pub fn create_bad_request<'a>(message: &'a str, details: Option<Value>) -> Response<Body> {
+        return Self::create_error(StatusCode::BAD_REQUEST, "bad_request", message, details);
+    }
+
+    pub fn create_not_found<'a>(message: &'a str, details: Option<Value>) -> Response<Body> {
+        return Self::create_error(StatusCode::NOT_FOUND, "not_found", message, details);
+    }
+
+    // The cause is not exposed to the client.
+    pub fn create_internal_server_error() -> Response<Body> {
+        return Self::create_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_server_error",
+            "Internal server error.",
+            None,
+        );
+    }
+}
+
+pub struct StorageKeyManager;
+
+impl StorageKeyManager {
+    const CHECKPOINT_PART: &'static str = "cp";
+    // The stored events are published to this channel.
+    pub const EVENT_CHANNEL: &'static str = "ev";
+    // The scripts of RedisEventStore build these keys too.
+    const EVENT_INDEX_PART: &'static str = "ix";
+    const EVENT_PENDING_PART: &'static str = "pd";
+    const EVENT_POSITION_PART: &'static str = "ps";
+    const EVENT_REMOVED_QUANTITY_PART: &'static str = "rq";
+    const EVENT_STREAM_PART: &'static str = "st";
+    const EVENT_STREAM_PENDING_PART: &'static str = "pd";
+    const EVENT_STREAM_PENDING_RECORD_PART: &'static str = "pr";
+    const EVENT_STREAM_POSITION_PART: &'static str = "ps";
+    // The '{part}:q' keys keep the last index that is taken, which the removed events keep too, so the
+    // saved quantity is the one of '{part}:rq' less. The names are kept, so that the saved keys are read.
+    pub const EVENT_APPROVAL_FOR_ALL_PART: &'static str = "aa";
+    pub const EVENT_APPROVAL_FOR_ALL_LAST_INDEX: &'static str = "aa:q";
+    pub const EVENT_APPROVAL_PART: &'static str = "ap";
+    pub const EVENT_APPROVAL_LAST_INDEX: &'static str = "ap:q";
+    pub const EVENT_COLLECTION_CREATED_PART: &'static str = "cc";
+    pub const EVENT_COLLECTION_CREATED_LAST_INDEX: &'static str = "cc:q";
+    pub const EVENT_TOKEN_MINTED_PART: &'static str = "tm";
+    pub const EVENT_TOKEN_MINTED_LAST_INDEX: &'static str = "tm:q";
+    pub const EVENT_TRANSFER_PART: &'static str = "tr";
+    pub const EVENT_TRANSFER_LAST_INDEX: &'static str = "tr:q";
+    // The ownership scripts of RedisEventStore build these keys too.
+    pub const OWNED_TOKEN_PART: &'static str = "ot";
+    const TOKEN_OWNERSHIP_PART: &'static str = "ow";
+    const TOKEN_OWNERSHIP_POSITION_PART: &'static str = "ps";
+    const TOKEN_OWNERSHIP_TRANSFER_PART: &'static str = "tr";
+    const SEPARATOR: &'static str = ":";
+
+    pub fn get_key_event_part(event_type: EventType) -> &'static str {
+        match event_type {
+            EventType::CollectionCreated => {
+                return Self::EVENT_COLLECTION_CREATED_PART;
+            }
+            EventType::TokenMinted => {
+                return Self::EVENT_TOKEN_MINTED_PART;
+            }
+            EventType::Transfer => {
+                return Self::EVENT_TRANSFER_PART;
+            }
+            EventType::Approval => {
+                return Self::EVENT_APPROVAL_PART;
+            }
+            EventType::ApprovalForAll => {
+                return Self::EVENT_APPROVAL_FOR_ALL_PART;
+            }
+        }
+    }
+
+    pub fn get_key_event_last_index(event_type: EventType) -> &'static str {
+        match event_type {
+            EventType::CollectionCreated => {
+                return Self::EVENT_COLLECTION_CREATED_LAST_INDEX;
+            }
+            EventType::TokenMinted => {
+                return Self::EVENT_TOKEN_MINTED_LAST_INDEX;
+            }
+            EventType::Transfer => {
+                return Self::EVENT_TRANSFER_LAST_INDEX;
+            }
+            EventType::Approval => {
+                return Self::EVENT_APPROVAL_LAST_INDEX;
+            }
+            EventType::ApprovalForAll => {
+                return Self::EVENT_APPROVAL_FOR_ALL_LAST_INDEX;
+            }
+        }
+    }
+
+    pub fn create_key_event<'a>(key_event_part: &'a str, event_index: i64) -> String {
+        return format!("{}{}{}", key_event_part, Self::SEPARATOR, event_index);
+    }
+
+    pub fn create_event_position(transaction_hash: H256, log_index: u64) -> String {
+        return format!("{:?}{}{}", transaction_hash, Self::SEPARATOR, log_index);
+    }
+
+    pub fn create_key_event_position<'a>(
+        key_event_part: &'a str,
+        event_position: &'a str,
+    ) -> String {
+        return format!(
+            "{}{}{}{}{}",
+            key_event_part,
+            Self::SEPARATOR,
+            Self::EVENT_POSITION_PART,
+            Self::SEPARATOR,
+            event_position
+        );
+    }
+
+    // The quantity of the removed events, which keep their indexes.
+    pub fn create_key_event_removed_quantity<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            key_event_part,
+            Self::SEPARATOR,
+            Self::EVENT_REMOVED_QUANTITY_PART
+        );
+    }
+
+    pub fn create_key_event_pending<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            key_event_part,
+            Self::SEPARATOR,
+            Self::EVENT_PENDING_PART
+        );
+    }
+
+    pub fn create_key_event_stream<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            key_event_part,
+            Self::SEPARATOR,
+            Self::EVENT_STREAM_PART
+        );
+    }
+
+    // The positions of the pending events, which are appended to the stream when they are final.
+    pub fn create_key_event_stream_pending<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            Self::create_key_event_stream(key_event_part),
+            Self::SEPARATOR,
+            Self::EVENT_STREAM_PENDING_PART
+        );
+    }
+
+    // The records of the pending events are kept here by their positions until they are final.
+    pub fn create_key_event_stream_pending_record<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            Self::create_key_event_stream(key_event_part),
+            Self::SEPARATOR,
+            Self::EVENT_STREAM_PENDING_RECORD_PART
+        );
+    }
+
+    // The id of every appended entry is kept here by the position of its event.
+    pub fn create_key_event_stream_position<'a>(key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{}",
+            Self::create_key_event_stream(key_event_part),
+            Self::SEPARATOR,
+            Self::EVENT_STREAM_POSITION_PART
+        );
+    }
+
+    // The scripts append the indexed value to this part.
+    pub fn create_key_event_index_part<'a>(key_event_part: &'a str, field: &'a str) -> String {
+        return format!(
+            "{}{}{}{}{}",
+            key_event_part,
+            Self::SEPARATOR,
+            Self::EVENT_INDEX_PART,
+            Self::SEPARATOR,
+            field
+        );
+    }
+
+    pub fn create_key_event_index<'a>(key_event_part: &'a str, event_index: EventIndex) -> String {
+        return format!(
+            "{}{}{:?}",
+            Self::create_key_event_index_part(key_event_part, event_index.get_field_name()),
+            Self::SEPARATOR,
+            event_index.get_address()
+        );
+    }
+
+    // The positions are padded, so that they are ordered like the logs in the chain.
+    pub fn create_event_stream_position(block_number: u64, log_index: u64) -> String {
+        return format!("{:020}-{:010}", block_number, log_index);
+    }
+
+    // The entries of a stream are ordered by their ids, which are ordered like the logs in the chain.
+    pub fn create_event_stream_id(block_number: u64, log_index: u64) -> String {
+        return format!("{}-{}", block_number, log_index);
+    }
+
+    pub fn create_owned_token(collection: Address, token_id: U256) -> String {
+        return format!("{:?}{}{}", collection, Self::SEPARATOR, token_id);
+    }
+
+    pub fn parse_owned_token<'a>(owned_token: &'a str) -> Option<OwnedToken> {
+        let (collection, token_id) = owned_token.split_once(Self::SEPARATOR)?;
+
+        return Some(OwnedToken {
+            collection: Address::from_str(collection).ok()?,
+            token_id: U256::from_dec_str(token_id).ok()?,
+        });
+    }
+
+    pub fn create_key_owned_token(owner: Address) -> String {
+        return format!("{}{}{:?}", Self::OWNED_TOKEN_PART, Self::SEPARATOR, owner);
+    }
+
+    pub fn create_key_token_ownership(collection: Address, token_id: U256) -> String {
+        return format!(
+            "{}{}{}",
+            Self::TOKEN_OWNERSHIP_PART,
+            Self::SEPARATOR,
+            Self::create_owned_token(collection, token_id)
+        );
+    }
+
+    // The positions of the indexed transfers of the token, which are ordered like the logs in the chain.
+    pub fn create_key_token_ownership_position(collection: Address, token_id: U256) -> String {
+        return format!(
+            "{}{}{}",
+            Self::create_key_token_ownership(collection, token_id),
+            Self::SEPARATOR,
+            Self::TOKEN_OWNERSHIP_POSITION_PART
+        );
+    }
+
+    // The ownership after every indexed transfer of the token is kept here by the position of the transfer.
+    pub fn create_key_token_ownership_transfer(collection: Address, token_id: U256) -> String {
+        return format!(
+            "{}{}{}",
+            Self::create_key_token_ownership(collection, token_id),
+            Self::SEPARATOR,
+            Self::TOKEN_OWNERSHIP_TRANSFER_PART
+        );
+    }
+
+    pub fn create_key_checkpoint<'a>(contract_address: Address, key_event_part: &'a str) -> String {
+        return format!(
+            "{}{}{:?}{}{}",
+            Self::CHECKPOINT_PART,
+            Self::SEPARATOR,
+            contract_address,
+            Self::SEPARATOR,
+            key_event_part
+        );
+    }
+}
+
+// The events of each type are addressed by cursors, which are ordered like the events are stored: the
+// one-based index in the keys mode, the entry id in the streams mode and the position in SQL. The cursor
+// of an event does not change until the event is retracted. The streams have only the final events, which
+// are appended when they become final.
+// A retracted event is neither returned nor counted.
+#[async_trait]
+pub trait EventStore: Clone + Send + Sync + 'static {
+    // An event is stored once per position, and the checkpoint of the contract never moves back.
+    // Returns false if the event is not stored, like the one that is stored already.
+    async fn append(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+        status: EventStatus,
+        data: String,
+    ) -> Result<bool, Error>;
+
+    async fn retract(
+        &self,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+    ) -> Result<(), Error>;
+
+    // The pending events up to the block become final.
+    async fn confirm(&self, event_type: EventType, final_block_number: u64) -> Result<(), Error>;
+
+    async fn get_by_cursor<'a>(
+        &self,
+        event_type: EventType,
+        cursor: &'a str,
+    ) -> Result<Option<String>, Error>;
+
+    // Returns up to the limit of the events in the order of their cursors. A cursor that the storage
+    // does not make is 'Error::NotFound'.
+    async fn get_page(
+        &self,
+        event_type: EventType,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error>;
+
+    // Only the events that are stored after it are after this cursor.
+    async fn get_last_cursor(&self, event_type: EventType) -> Result<String, Error>;
+
+    async fn count(&self, event_type: EventType) -> Result<i64, Error>;
+
+    // The indexed events are ordered by their positions in the chain, and their cursors are their checkpoints.
+    // An index that is not kept for the event type is empty.
+    async fn count_indexed(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+    ) -> Result<i64, Error>;
+
+    async fn get_indexed_page(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error>;
+
+    async fn get_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<Option<EventCheckpoint>, Error>;
+
+    async fn set_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error>;
+
+    async fn remove_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<(), Error>;
+
+    // Every indexed transfer of the token is kept, and the latest one is its ownership. Indexing the same
+    // transfer again is harmless.
+    async fn index_ownership(&self, token_ownership: TokenOwnership) -> Result<(), Error>;
+
+    // The transfer is dropped, and the ownership is the latest of the remaining ones or nothing.
+    async fn retract_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error>;
+
+    async fn get_token_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<Option<TokenOwnership>, Error>;
+
+    async fn get_owned_token_registry(&self, owner: Address) -> Result<Vec<OwnedToken>, Error>;
+
+    // Yields the events that are stored, confirmed or retracted after the subscription.
+    async fn subscribe(&self) -> Result<BoxStream<'static, EventNotification>, Error>;
+}
+
+#[derive(Clone)]
+pub struct RedisEventStore {
+    connection_pool: Pool<RedisConnectionManager>,
+    redis_url: String,
+    storage_mode: StorageMode,
+}
+
+impl RedisEventStore {
+    const MIGRATION_PAGE_EVENT_QUANTITY: i64 = 100;
+    const PAGE_KEY_QUANTITY: i64 = 1000;
+    // KEYS: pending events. ARGV: event key part, greatest final block number, event type, event channel.
+    const CONFIRM_SCRIPT: &'static str = r#"
+local event_position_registry = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[2])
+for _, event_position in ipairs(event_position_registry) do
+    local index = redis.call('GET', ARGV[1] .. ':ps:' .. event_position)
+    if index then
+        local key = ARGV[1] .. ':' .. index
+        local data = redis.call('GET', key)
+        if data then
+            local record = cjson.decode(data)
+            record.status = 'final'
+            data = cjson.encode(record)
+            redis.call('SET', key, data)
+            redis.call('PUBLISH', ARGV[4], '{"type":"' .. ARGV[3] .. '","change":"confirmed","cursor":"' .. index .. '","record":' .. data .. '}')
+        end
+    end
+    redis.call('ZREM', KEYS[1], event_position)
+end
+return #event_position_registry
+"#;
+    // KEYS: last event index, event position, pending events, checkpoint.
+    // ARGV: event key part, event record, event position, block number, log index, is pending, checkpoint,
+    // event type, event channel, then the index key part and the field of every index.
+    // An event that is already stored under its position is not stored again, and the checkpoint never moves back.
+    // The stored event is published in the same form as 'EventNotification'. The index member is ordered
+    // by the position in the chain and is added again, so that replaying logs indexes the older events.
+    // Returns 1 if the event is stored and 0 if it is stored already.
+    const PUSH_SCRIPT: &'static str = r#"
+local result = 0
+if redis.call('EXISTS', KEYS[2]) == 0 then
+    result = 1
+    local index = redis.call('INCR', KEYS[1])
+    redis.call('SET', ARGV[1] .. ':' .. index, ARGV[2])
+    redis.call('SET', KEYS[2], index)
+    if ARGV[6] == '1' then
+        redis.call('ZADD', KEYS[3], ARGV[4], ARGV[3])
+    end
+    redis.call('PUBLISH', ARGV[9], '{"type":"' .. ARGV[8] .. '","change":"appended","cursor":"' .. index .. '","record":' .. ARGV[2] .. '}')
+end
+local record = cjson.decode(ARGV[2])
+local member = string.format('%020d:%010d:%s', tonumber(ARGV[4]), tonumber(ARGV[5]), record.event.tx_hash)
+for i = 10, #ARGV, 2 do
+    redis.call('ZADD', ARGV[i] .. ':' .. record.event[ARGV[i + 1]], 0, member)
+end
+local checkpoint = redis.call('GET', KEYS[4])
+if checkpoint then
+    checkpoint = cjson.decode(checkpoint)
+    local block_number = tonumber(ARGV[4])
+    if checkpoint.block_number > block_number or (checkpoint.block_number == block_number and checkpoint.log_index >= tonumber(ARGV[5])) then
+        return result
+    end
+end
+redis.call('SET', KEYS[4], ARGV[7])
+return result
+"#;
+    // Is prepended to the ownership scripts. The ownership is the latest of the transfers of the token, and
+    // the token is in the owned tokens of its owner unless it is burned. The ownership that was indexed before
+    // the transfers were kept is kept as the first transfer.
+    const OWNERSHIP_FUNCTION: &'static str = r#"
+local function keep_ownership(key_token_ownership, key_position, key_transfer)
+    if redis.call('EXISTS', key_position) == 1 then
+        return
+    end
+    local token_ownership = redis.call('GET', key_token_ownership)
+    if not token_ownership then
+        return
+    end
+    local token_ownership_ = cjson.decode(token_ownership)
+    local position = string.format('%020d-%010d', token_ownership_.block_number, token_ownership_.log_index)
+    redis.call('ZADD', key_position, 0, position)
+    redis.call('HSET', key_transfer, position, token_ownership)
+end
+local function update_ownership(key_token_ownership, key_position, key_transfer, owned_token, owned_token_part)
+    local token_ownership = redis.call('GET', key_token_ownership)
+    if token_ownership then
+        redis.call('SREM', owned_token_part .. ':' .. cjson.decode(token_ownership).owner, owned_token)
+    end
+    local position = redis.call('ZREVRANGEBYLEX', key_position, '+', '-', 'LIMIT', 0, 1)[1]
+    if not position then
+        redis.call('DEL', key_token_ownership)
+        return
+    end
+    token_ownership = redis.call('HGET', key_transfer, position)
+    redis.call('SET', key_token_ownership, token_ownership)
+    local owner = cjson.decode(token_ownership).owner
+    if not string.match(owner, '^0x0+$') then
+        redis.call('SADD', owned_token_part .. ':' .. owner, owned_token)
+    end
+end
+"#;
+    // KEYS: token ownership, transfer positions of the token, transfers of the token.
+    // ARGV: token ownership, transfer position, owned token, owned token key part.
+    // The transfer is kept under its position, so that replaying logs is harmless.
+    const OWNERSHIP_SCRIPT: &'static str = r#"
+keep_ownership(KEYS[1], KEYS[2], KEYS[3])
+redis.call('ZADD', KEYS[2], 0, ARGV[2])
+redis.call('HSET', KEYS[3], ARGV[2], ARGV[1])
+update_ownership(KEYS[1], KEYS[2], KEYS[3], ARGV[3], ARGV[4])
+return 1
+"#;
+    // KEYS: token ownership, transfer positions of the token, transfers of the token.
+    // ARGV: transfer position, owned token, owned token key part.
+    const OWNERSHIP_RETRACT_SCRIPT: &'static str = r#"
+keep_ownership(KEYS[1], KEYS[2], KEYS[3])
+if redis.call('ZREM', KEYS[2], ARGV[1]) == 0 then
+    return 0
+end
+redis.call('HDEL', KEYS[3], ARGV[1])
+update_ownership(KEYS[1], KEYS[2], KEYS[3], ARGV[2], ARGV[3])
+return 1
+"#;
+    // KEYS: removed event quantity, event position, pending events.
+    // ARGV: event key part, event position, event type, event channel, then the index key part and the field
+    // of every index.
+    // The retracted event is kept under its index with the removed status. Its position is released, so
+    // that the log is stored again if a later block includes it.
+    const RETRACT_SCRIPT: &'static str = r#"
+local index = redis.call('GET', KEYS[2])
+if not index then
+    return 0
+end
+local key = ARGV[1] .. ':' .. index
+local record = cjson.decode(redis.call('GET', key))
+local member = string.format('%020d:%010d:%s', record.event.block_number, record.event.log_index, record.event.tx_hash)
+for i = 5, #ARGV, 2 do
+    redis.call('ZREM', ARGV[i] .. ':' .. record.event[ARGV[i + 1]], member)
+end
+record.status = 'removed'
+local data = cjson.encode(record)
+redis.call('SET', key, data)
+redis.call('PUBLISH', ARGV[4], '{"type":"' .. ARGV[3] .. '","change":"retracted","cursor":"' .. index .. '","record":' .. data .. '}')
+redis.call('INCR', KEYS[1])
+redis.call('DEL', KEYS[2])
+redis.call('ZREM', KEYS[3], ARGV[2])
+return 1
+"#;
+    // KEYS: event stream, pending events, event stream ids, pending event records, checkpoint.
+    // ARGV: event position, event record, block number, log index, is pending, checkpoint, event type, event channel,
+    // event stream id, then the index key part and the field of every index.
+    // The stream has only the final events, so a pending one is kept by its position until it is final. The appended
+    // event is published with its id. Returns 1 if the event is stored and 0 if it is stored already.
+    const STREAM_PUSH_SCRIPT: &'static str = r#"
+local result = 0
+if redis.call('HEXISTS', KEYS[3], ARGV[1]) == 0 and redis.call('HEXISTS', KEYS[4], ARGV[1]) == 0 then
+    if ARGV[5] == '1' then
+        redis.call('HSET', KEYS[4], ARGV[1], ARGV[2])
+        redis.call('ZADD', KEYS[2], ARGV[3], ARGV[1])
+    else
+        local id = append(KEYS[1], ARGV[9], ARGV[2], ARGV[3], ARGV[4])
+        redis.call('HSET', KEYS[3], ARGV[1], id)
+        redis.call('PUBLISH', ARGV[8], '{"type":"' .. ARGV[7] .. '","change":"appended","cursor":"' .. id .. '","record":' .. ARGV[2] .. '}')
+    end
+    result = 1
+end
+local record = cjson.decode(ARGV[2])
+local member = string.format('%020d:%010d:%s', tonumber(ARGV[3]), tonumber(ARGV[4]), record.event.tx_hash)
+for i = 10, #ARGV, 2 do
+    redis.call('ZADD', ARGV[i] .. ':' .. record.event[ARGV[i + 1]], 0, member)
+end
+local checkpoint = redis.call('GET', KEYS[5])
+if checkpoint then
+    checkpoint = cjson.decode(checkpoint)
+    local block_number = tonumber(ARGV[3])
+    if checkpoint.block_number > block_number or (checkpoint.block_number == block_number and checkpoint.log_index >= tonumber(ARGV[4])) then
+        return result
+    end
+end
+redis.call('SET', KEYS[5], ARGV[6])
+return result
+"#;
+    // KEYS: event stream, pending events, event stream ids, pending event records.
+    // ARGV: greatest final block number, event type, event channel.
+    // The final events are appended to the stream once, and they are published as appended, because the stream
+    // does not have them before. The pending events of a block are ordered by their padded positions, so they
+    // are appended in the order of the chain.
+    const STREAM_CONFIRM_SCRIPT: &'static str = r#"
+local position_registry = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ARGV[1])
+for _, position in ipairs(position_registry) do
+    local data = redis.call('HGET', KEYS[4], position)
+    if data then
+        local record = cjson.decode(data)
+        record.status = 'final'
+        data = cjson.encode(record)
+        local block_number = string.format('%d', record.event.block_number)
+        local log_index = string.format('%d', record.event.log_index)
+        local id = append(KEYS[1], block_number .. '-' .. log_index, data, block_number, log_index)
+        redis.call('HSET', KEYS[3], position, id)
+        redis.call('HDEL', KEYS[4], position)
+        redis.call('PUBLISH', ARGV[3], '{"type":"' .. ARGV[2] .. '","change":"appended","cursor":"' .. id .. '","record":' .. data .. '}')
+    end
+    redis.call('ZREM', KEYS[2], position)
+end
+return #position_registry
+"#;
+    // KEYS: event stream, pending events, event stream ids, pending event records.
+    // ARGV: event position, event type, event channel, then the index key part and the field of every index.
+    // A pending event is dropped. The entry of a final one is deleted, and the retracted event is published
+    // with its id. The log is stored again if a later block includes it.
+    const STREAM_RETRACT_SCRIPT: &'static str = r#"
+local data = redis.call('HGET', KEYS[4], ARGV[1])
+local id = false
+if data then
+    redis.call('HDEL', KEYS[4], ARGV[1])
+    redis.call('ZREM', KEYS[2], ARGV[1])
+else
+    id = redis.call('HGET', KEYS[3], ARGV[1])
+    if not id then
+        return 0
+    end
+    local entry_registry = redis.call('XRANGE', KEYS[1], id, id)
+    if #entry_registry > 0 then
+        data = entry_registry[1][2][2]
+    end
+    redis.call('XDEL', KEYS[1], id)
+    redis.call('HDEL', KEYS[3], ARGV[1])
+end
+if data then
+    local record = cjson.decode(data)
+    local member = string.format('%020d:%010d:%s', record.event.block_number, record.event.log_index, record.event.tx_hash)
+    for i = 4, #ARGV, 2 do
+        redis.call('ZREM', ARGV[i] .. ':' .. record.event[ARGV[i + 1]], member)
+    end
+    if id then
+        record.status = 'removed'
+        redis.call('PUBLISH', ARGV[3], '{"type":"' .. ARGV[2] .. '","change":"retracted","cursor":"' .. id .. '","record":' .. cjson.encode(record) .. '}')
+    end
+end
+return 1
+"#;
+    // KEYS: event stream, pending events, event stream ids, pending event records.
+    // ARGV: event position, event record, block number, log index, is pending, event stream id.
+    // Returns 1 if the event is moved and 0 if it is there already.
+    // The indexes are the same in both modes, so they are not migrated.
+    const STREAM_MIGRATE_SCRIPT: &'static str = r#"
+if redis.call('HEXISTS', KEYS[3], ARGV[1]) == 1 or redis.call('HEXISTS', KEYS[4], ARGV[1]) == 1 then
+    return 0
+end
+if ARGV[5] == '1' then
+    redis.call('HSET', KEYS[4], ARGV[1], ARGV[2])
+    redis.call('ZADD', KEYS[2], ARGV[3], ARGV[1])
+else
+    local id = append(KEYS[1], ARGV[6], ARGV[2], ARGV[3], ARGV[4])
+    redis.call('HSET', KEYS[3], ARGV[1], id)
+end
+return 1
+"#;
+    // Is prepended to the scripts that append to a stream. The entry is appended under the id of its event.
+    // The ids only grow, so a log that is older than the last entry, like the one of a collection that is
+    // followed later, is appended right after the last entry. Its entry keeps its block number and log index.
+    const STREAM_APPEND_FUNCTION: &'static str = r#"
+local function append(key, id, data, block_number, log_index)
+    local result = redis.pcall('XADD', key, id, 'record', data, 'block_number', block_number, 'log_index', log_index)
+    if type(result) == 'string' then
+        return result
+    end
+    local last_id = '0-0'
+    local stream_information = redis.call('XINFO', 'STREAM', key)
+    for i = 1, #stream_information, 2 do
+        if stream_information[i] == 'last-generated-id' then
+            last_id = stream_information[i + 1]
+        end
+    end
+    local milliseconds, sequence_number = string.match(last_id, '^(%d+)-(%d+)$')
+    local next_id = milliseconds .. '-' .. string.format('%d', tonumber(sequence_number) + 1)
+    return redis.call('XADD', key, next_id, 'record', data, 'block_number', block_number, 'log_index', log_index)
+end
+"#;
+    // KEYS: event index, event stream, event stream ids, pending event records.
+    // ARGV: event key part, is stream, is offset, offset or first member, limit.
+    // Returns the checkpoint and the record of every event of the page. The event of a member is read from
+    // where the storage mode keeps it.
+    const INDEX_PAGE_SCRIPT: &'static str = r#"
+local member_registry
+if ARGV[3] == '1' then
+    member_registry = redis.call('ZRANGE', KEYS[1], ARGV[4], tonumber(ARGV[4]) + tonumber(ARGV[5]) - 1)
+else
+    member_registry = redis.call('ZRANGEBYLEX', KEYS[1], ARGV[4], '+', 'LIMIT', 0, ARGV[5])
+end
+local result = {}
+for _, member in ipairs(member_registry) do
+    local block_number, log_index, transaction_hash = string.match(member, '^(%d+):(%d+):(.+)$')
+    local data = false
+    if ARGV[2] == '1' then
+        local position = string.format('%020d-%010d', tonumber(block_number), tonumber(log_index))
+        local id = redis.call('HGET', KEYS[3], position)
+        if id then
+            local entry_registry = redis.call('XRANGE', KEYS[2], id, id)
+            if #entry_registry > 0 then
+                data = entry_registry[1][2][2]
+            end
+        else
+            data = redis.call('HGET', KEYS[4], position)
+        end
+    else
+        local index = redis.call('GET', ARGV[1] .. ':ps:' .. transaction_hash .. ':' .. string.format('%d', tonumber(log_index)))
+        if index then
+            data = redis.call('GET', ARGV[1] .. ':' .. index)
+        end
+    end
+    if data then
+        table.insert(result, string.format('%d:%d', tonumber(block_number), tonumber(log_index)))
+        table.insert(result, data)
+    end
+end
+return result
+"#;
+
+    pub fn new(
+        connection_pool: Pool<RedisConnectionManager>,
+        redis_url: String,
+        storage_mode: StorageMode,
+    ) -> Self {
+        return Self {
+            connection_pool,
+            redis_url,
+            storage_mode,
+        };
+    }
+
+    // The scripts take the key part and the field of every index after their own arguments.
+    fn add_event_index_registry<'a, 'b>(
+        script_invocation: &'a mut ScriptInvocation<'b>,
+        event_type: EventType,
+    ) -> () {
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        for field in event_type.get_index_field_registry().iter() {
+            script_invocation
+                .arg(StorageKeyManager::create_key_event_index_part(
+                    key_event_part,
+                    field,
+                ))
+                .arg(*field);
+        }
+
+        return ();
+    }
+
+    // The scripts return the cursor and the record of every event one after another.
+    fn create_event_entry_registry(result: Vec<String>) -> Vec<EventEntry> {
+        return result
+            .chunks_exact(2)
+            .map(|event_entry| EventEntry {
+                cursor: event_entry[0].clone(),
+                data: event_entry[1].clone(),
+            })
+            .collect::<Vec<EventEntry>>();
+    }
+
+    // Prepends the function that appends the entries to the script.
+    fn create_stream_append_script<'a>(script: &'a str) -> Script {
+        return Script::new((Self::STREAM_APPEND_FUNCTION.to_string() + script).as_str());
+    }
+
+    fn create_ownership_script<'a>(script: &'a str) -> Script {
+        return Script::new((Self::OWNERSHIP_FUNCTION.to_string() + script).as_str());
+    }
+
+    // The ids are two numbers, which a cursor is checked for before it is read.
+    fn is_stream_id<'a>(cursor: &'a str) -> bool {
+        match cursor.split_once('-') {
+            Some((milliseconds, sequence_number)) => {
+                return u64::from_str(milliseconds).is_ok()
+                    && u64::from_str(sequence_number).is_ok();
+            }
+            None => {
+                return false;
+            }
+        }
+    }
+
+    async fn get_connection(&self) -> Result<PooledConnection<'_, RedisConnectionManager>, Error> {
+        match self.connection_pool.get().await {
+            Ok(pooled_connection) => {
+                return Ok(pooled_connection);
+            }
+            Err(error) => {
+                return Err(Error::Pool {
+                    context: "Failed to get a Redis connection".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    // The keys of the page are read at once, from right after the cursor, or from the offset if no event
+    // is removed. Otherwise the removed events before the offset are not known, so the keys are read from
+    // the first one. The removed events are dropped after they are read.
+    async fn get_key_event_page<'a>(
+        connection: &'a mut RedisConnection,
+        event_type: EventType,
+        after_index: i64,
+        skipped_quantity: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, RedisError> {
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let (last_index, removed_quantity) = redis::cmd("MGET")
+            .arg(StorageKeyManager::get_key_event_last_index(event_type))
+            .arg(StorageKeyManager::create_key_event_removed_quantity(
+                key_event_part,
+            ))
+            .query_async::<_, (Option<i64>, Option<i64>)>(&mut *connection)
+            .await?;
+
+        let (mut after_index_, mut skipped_quantity_) = if removed_quantity.unwrap_or(0) == 0 {
+            (after_index.saturating_add(skipped_quantity), 0)
+        } else {
+            (after_index, skipped_quantity)
+        };
+
+        let last_index_ = last_index.unwrap_or(0);
+
+        let mut event_entry_registry: Vec<EventEntry> = vec![];
+
+        while after_index_ < last_index_ && (event_entry_registry.len() as i64) < limit {
+            let key_quantity = cmp::min(
+                skipped_quantity_.saturating_add(limit - event_entry_registry.len() as i64),
+                Self::PAGE_KEY_QUANTITY,
+            );
+
+            let page_last_index = cmp::min(after_index_.saturating_add(key_quantity), last_index_);
+
+            let key_event_registry = ((after_index_ + 1)..=page_last_index)
+                .map(|index| StorageKeyManager::create_key_event(key_event_part, index))
+                .collect::<Vec<String>>();
+
+            let data_registry = redis::cmd("MGET")
+                .arg(key_event_registry)
+                .query_async::<_, Vec<Option<String>>>(&mut *connection)
+                .await?;
+
+            for (index, data) in ((after_index_ + 1)..=page_last_index).zip(data_registry) {
+                let data_ = match data {
+                    Some(data_) => data_,
+                    None => {
+                        continue;
+                    }
+                };
+
+                if let Ok(EventStatus::Removed) = EventStatusUpdater::read(data_.as_str()) {
+                    continue;
+                }
+
+                if skipped_quantity_ > 0 {
+                    skipped_quantity_ -= 1;
+                } else if (event_entry_registry.len() as i64) < limit {
+                    event_entry_registry.push(EventEntry {
+                        cursor: index.to_string(),
+                        data: data_,
+                    });
+                }
+            }
+
+            after_index_ = page_last_index;
+        }
+
+        return Ok(event_entry_registry);
+    }
+
+    // The numbered keys are left in place. The events are appended in the order of their positions,
+    // which a subscriber that is stopped keeps.
+    pub async fn migrate_to_streams(&self) -> Result<Vec<EventMigration>, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let mut event_migration_registry: Vec<EventMigration> = vec![];
+
+        for event_type in EventType::REGISTRY.into_iter() {
+            let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+            let event_last_index = match connection
+                .get::<'_, &'_ str, Option<i64>>(StorageKeyManager::get_key_event_last_index(
+                    event_type,
+                ))
+                .await
+            {
+                Ok(event_last_index_) => event_last_index_.unwrap_or(0),
+                Err(error) => {
+                    return Err(Error::Redis {
+                        context: "Failed to get the last event index".to_string(),
+                        source: error,
+                    });
+                }
+            };
+
+            let mut migrated_event_registry: Vec<(EventCheckpoint, bool, String)> = vec![];
+
+            let mut after_index: i64 = 0;
+
+            while after_index < event_last_index {
+                let last_index = cmp::min(
+                    after_index + Self::MIGRATION_PAGE_EVENT_QUANTITY,
+                    event_last_index,
+                );
+
+                let key_event_registry = ((after_index + 1)..=last_index)
+                    .map(|index| StorageKeyManager::create_key_event(key_event_part, index))
+                    .collect::<Vec<String>>();
+
+                let data_registry = match redis::cmd("MGET")
+                    .arg(key_event_registry)
+                    .query_async::<_, Vec<Option<String>>>(connection)
+                    .await
+                {
+                    Ok(data_registry_) => data_registry_,
+                    Err(error) => {
+                        return Err(Error::Redis {
+                            context: "Failed to get the events".to_string(),
+                            source: error,
+                        });
+                    }
+                };
+
+                for data in data_registry.into_iter().flatten() {
+                    let event_record =
+                        match serde_json::from_str::<EventRecord<Value>>(data.as_str()) {
+                            Ok(event_record_) => event_record_,
+                            Err(error) => {
+                                return Err(Error::Decoding {
+                                    context: "Failed to parse the stored event".to_string(),
+                                    source: Some(Box::from(error)),
+                                });
+                            }
+                        };
+
+                    if event_record.status == EventStatus::Removed {
+                        continue;
+                    }
+
+                    let event_checkpoint = match (
+                        event_record.event["block_number"].as_u64(),
+                        event_record.event["log_index"].as_u64(),
+                    ) {
+                        (Some(block_number), Some(log_index)) => EventCheckpoint {
+                            block_number,
+                            log_index,
+                        },
+                        _ => {
+                            return Err(Error::Decoding {
+                                context: "The stored event has no position".to_string(),
+                                source: None,
+                            });
+                        }
+                    };
+
+                    migrated_event_registry.push((
+                        event_checkpoint,
+                        event_record.status == EventStatus::Pending,
+                        data,
+                    ));
+                }
+
+                after_index = last_index;
+            }
+
+            // The entries of the numbered keys are in the order of storing, which is not always the order
+            // of the chain.
+            migrated_event_registry.sort_by_key(|(event_checkpoint, _, _)| *event_checkpoint);
+
+            let mut event_migration = EventMigration {
+                event_type,
+                quantity: migrated_event_registry.len() as i64,
+                migrated_quantity: 0,
+            };
+
+            for (event_checkpoint, is_pending, data) in migrated_event_registry.into_iter() {
+                let result = match Self::create_stream_append_script(Self::STREAM_MIGRATE_SCRIPT)
+                    .key(StorageKeyManager::create_key_event_stream(key_event_part))
+                    .key(StorageKeyManager::create_key_event_stream_pending(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_position(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_pending_record(
+                        key_event_part,
+                    ))
+                    .arg(StorageKeyManager::create_event_stream_position(
+                        event_checkpoint.block_number,
+                        event_checkpoint.log_index,
+                    ))
+                    .arg(data)
+                    .arg(event_checkpoint.block_number)
+                    .arg(event_checkpoint.log_index)
+                    .arg(is_pending)
+                    .arg(StorageKeyManager::create_event_stream_id(
+                        event_checkpoint.block_number,
+                        event_checkpoint.log_index,
+                    ))
+                    .invoke_async::<_, i64>(connection)
+                    .await
+                {
+                    Ok(result_) => result_,
+                    Err(error) => {
+                        return Err(Error::Redis {
+                            context: "Failed to migrate the event".to_string(),
+                            source: error,
+                        });
+                    }
+                };
+
+                if result == 1 {
+                    event_migration.migrated_quantity += 1;
+                }
+            }
+
+            event_migration_registry.push(event_migration);
+        }
+
+        return Ok(event_migration_registry);
+    }
+}
+
+#[async_trait]
+impl EventStore for RedisEventStore {
+    async fn append(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+        status: EventStatus,
+        data: String,
+    ) -> Result<bool, Error> {
+        let checkpoint_data = match serde_json::to_string(&checkpoint) {
+            Ok(checkpoint_data_) => checkpoint_data_,
+            Err(error) => {
+                return Err(Error::Serialization {
+                    context: "Failed to serialize the checkpoint".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let script_invocation = match self.storage_mode {
+            StorageMode::Keys => {
+                let event_position = StorageKeyManager::create_event_position(
+                    transaction_hash,
+                    checkpoint.log_index,
+                );
+
+                let script = Script::new(Self::PUSH_SCRIPT);
+
+                let mut script_invocation =
+                    script.key(StorageKeyManager::get_key_event_last_index(event_type));
+
+                script_invocation
+                    .key(StorageKeyManager::create_key_event_position(
+                        key_event_part,
+                        event_position.as_str(),
+                    ))
+                    .key(StorageKeyManager::create_key_event_pending(key_event_part))
+                    .key(StorageKeyManager::create_key_checkpoint(
+                        contract_address,
+                        key_event_part,
+                    ))
+                    .arg(key_event_part)
+                    .arg(data)
+                    .arg(event_position.as_str())
+                    .arg(checkpoint.block_number)
+                    .arg(checkpoint.log_index)
+                    .arg(status == EventStatus::Pending)
+                    .arg(checkpoint_data)
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL);
+
+                Self::add_event_index_registry(&mut script_invocation, event_type);
+
+                script_invocation
+                    .invoke_async::<_, i64>(connection)
+                    .await
+                    .map(|result| result == 1)
+            }
+            StorageMode::Streams => {
+                let script = Self::create_stream_append_script(Self::STREAM_PUSH_SCRIPT);
+
+                let mut script_invocation =
+                    script.key(StorageKeyManager::create_key_event_stream(key_event_part));
+
+                script_invocation
+                    .key(StorageKeyManager::create_key_event_stream_pending(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_position(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_pending_record(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_checkpoint(
+                        contract_address,
+                        key_event_part,
+                    ))
+                    .arg(StorageKeyManager::create_event_stream_position(
+                        checkpoint.block_number,
+                        checkpoint.log_index,
+                    ))
+                    .arg(data)
+                    .arg(checkpoint.block_number)
+                    .arg(checkpoint.log_index)
+                    .arg(status == EventStatus::Pending)
+                    .arg(checkpoint_data)
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL)
+                    .arg(StorageKeyManager::create_event_stream_id(
+                        checkpoint.block_number,
+                        checkpoint.log_index,
+                    ));
+
+                Self::add_event_index_registry(&mut script_invocation, event_type);
+
+                script_invocation
+                    .invoke_async::<_, i64>(connection)
+                    .await
+                    .map(|result| result == 1)
+            }
+        };
+
+        match script_invocation {
+            Ok(is_appended) => {
+                return Ok(is_appended);
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to push the event".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn retract(
+        &self,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+    ) -> Result<(), Error> {
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let script_invocation = match self.storage_mode {
+            StorageMode::Keys => {
+                let event_position = StorageKeyManager::create_event_position(
+                    transaction_hash,
+                    checkpoint.log_index,
+                );
+
+                let script = Script::new(Self::RETRACT_SCRIPT);
+
+                let mut script_invocation = script.key(
+                    StorageKeyManager::create_key_event_removed_quantity(key_event_part),
+                );
+
+                script_invocation
+                    .key(StorageKeyManager::create_key_event_position(
+                        key_event_part,
+                        event_position.as_str(),
+                    ))
+                    .key(StorageKeyManager::create_key_event_pending(key_event_part))
+                    .arg(key_event_part)
+                    .arg(event_position.as_str())
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL);
+
+                Self::add_event_index_registry(&mut script_invocation, event_type);
+
+                script_invocation.invoke_async::<_, i64>(connection).await
+            }
+            StorageMode::Streams => {
+                let script = Script::new(Self::STREAM_RETRACT_SCRIPT);
+
+                let mut script_invocation =
+                    script.key(StorageKeyManager::create_key_event_stream(key_event_part));
+
+                script_invocation
+                    .key(StorageKeyManager::create_key_event_stream_pending(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_position(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_pending_record(
+                        key_event_part,
+                    ))
+                    .arg(StorageKeyManager::create_event_stream_position(
+                        checkpoint.block_number,
+                        checkpoint.log_index,
+                    ))
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL);
+
+                Self::add_event_index_registry(&mut script_invocation, event_type);
+
+                script_invocation.invoke_async::<_, i64>(connection).await
+            }
+        };
+
+        if let Err(error) = script_invocation {
+            return Err(Error::Redis {
+                context: "Failed to retract the event".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn confirm(&self, event_type: EventType, final_block_number: u64) -> Result<(), Error> {
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let script_invocation = match self.storage_mode {
+            StorageMode::Keys => {
+                Script::new(Self::CONFIRM_SCRIPT)
+                    .key(StorageKeyManager::create_key_event_pending(key_event_part))
+                    .arg(key_event_part)
+                    .arg(final_block_number)
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL)
+                    .invoke_async::<_, u64>(connection)
+                    .await
+            }
+            StorageMode::Streams => {
+                Self::create_stream_append_script(Self::STREAM_CONFIRM_SCRIPT)
+                    .key(StorageKeyManager::create_key_event_stream(key_event_part))
+                    .key(StorageKeyManager::create_key_event_stream_pending(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_position(
+                        key_event_part,
+                    ))
+                    .key(StorageKeyManager::create_key_event_stream_pending_record(
+                        key_event_part,
+                    ))
+                    .arg(final_block_number)
+                    .arg(event_type.get_name())
+                    .arg(StorageKeyManager::EVENT_CHANNEL)
+                    .invoke_async::<_, u64>(connection)
+                    .await
+            }
+        };
+
+        if let Err(error) = script_invocation {
+            return Err(Error::Redis {
+                context: "Failed to confirm the events".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn get_by_cursor<'a>(
+        &self,
+        event_type: EventType,
+        cursor: &'a str,
+    ) -> Result<Option<String>, Error> {
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        match self.storage_mode {
+            StorageMode::Keys => {
+                let index = match i64::from_str(cursor) {
+                    Ok(index_) if index_ > 0 => index_,
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+
+                let data = match connection
+                    .get::<'_, String, Option<String>>(StorageKeyManager::create_key_event(
+                        key_event_part,
+                        index,
+                    ))
+                    .await
+                {
+                    Ok(data_) => data_,
+                    Err(error) => {
+                        return Err(Error::Redis {
+                            context: "Failed to get the event".to_string(),
+                            source: error,
+                        });
+                    }
+                };
+
+                match data {
+                    Some(data_) => {
+                        if EventStatusUpdater::read(data_.as_str())? == EventStatus::Removed {
+                            return Ok(None);
+                        }
+
+                        return Ok(Some(data_));
+                    }
+                    None => {
+                        return Ok(None);
+                    }
+                }
+            }
+            StorageMode::Streams => {
+                if !Self::is_stream_id(cursor) {
+                    return Ok(None);
+                }
+
+                match connection
+                    .xrange_count::<'_, String, &'_ str, &'_ str, i64, StreamRangeReply>(
+                        StorageKeyManager::create_key_event_stream(key_event_part),
+                        cursor,
+                        cursor,
+                        1,
+                    )
+                    .await
+                {
+                    Ok(stream_range_reply) => {
+                        return Ok(stream_range_reply
+                            .ids
+                            .into_iter()
+                            .next()
+                            .and_then(|stream_id| stream_id.get::<String>("record")));
+                    }
+                    Err(error) => {
+                        return Err(Error::Redis {
+                            context: "Failed to get the event".to_string(),
+                            source: error,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    async fn get_page(
+        &self,
+        event_type: EventType,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        if limit <= 0 {
+            return Ok(vec![]);
+        }
+
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let event_entry_registry = match self.storage_mode {
+            StorageMode::Keys => {
+                let (after_index, skipped_quantity) = match page_start {
+                    EventPageStart::Offset(offset) => (0, cmp::max(offset, 0)),
+                    EventPageStart::After(cursor) => match i64::from_str(cursor.as_str()) {
+                        Ok(index) if index >= 0 => (index, 0),
+                        _ => {
+                            return Err(Error::from_cursor(cursor.as_str()));
+                        }
+                    },
+                };
+
+                Self::get_key_event_page(
+                    connection,
+                    event_type,
+                    after_index,
+                    skipped_quantity,
+                    limit,
+                )
+                .await
+            }
+            StorageMode::Streams => {
+                // The page starts after the id of the cursor, which may be deleted already. A stream is not
+                // addressed by offset, so the entries before the offset are read too. The pending events
+                // are not in the stream yet.
+                let (start, skipped_quantity) = match page_start {
+                    EventPageStart::Offset(offset) => ("-".to_string(), offset),
+                    EventPageStart::After(cursor) => {
+                        if !Self::is_stream_id(cursor.as_str()) {
+                            return Err(Error::from_cursor(cursor.as_str()));
+                        }
+
+                        (format!("({}", cursor), 0)
+                    }
+                };
+
+                connection
+                    .xrange_count::<'_, String, String, &'_ str, i64, StreamRangeReply>(
+                        StorageKeyManager::create_key_event_stream(key_event_part),
+                        start,
+                        "+",
+                        skipped_quantity.saturating_add(limit),
+                    )
+                    .await
+                    .map(|stream_range_reply| {
+                        return stream_range_reply
+                            .ids
+                            .into_iter()
+                            .skip(skipped_quantity as usize)
+                            .filter_map(|stream_id| {
+                                return stream_id.get::<String>("record").map(|data| {
+                                    return EventEntry {
+                                        cursor: stream_id.id,
+                                        data,
+                                    };
+                                });
+                            })
+                            .collect::<Vec<EventEntry>>();
+                    })
+            }
+        };
+
+        match event_entry_registry {
+            Ok(event_entry_registry_) => {
+                return Ok(event_entry_registry_);
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the events".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_last_cursor(&self, event_type: EventType) -> Result<String, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let cursor = match self.storage_mode {
+            StorageMode::Keys => connection
+                .get::<'_, &'_ str, Option<i64>>(StorageKeyManager::get_key_event_last_index(
+                    event_type,
+                ))
+                .await
+                .map(|last_index| last_index.unwrap_or(0).to_string()),
+            StorageMode::Streams => connection
+                .xrevrange_count::<'_, String, &'_ str, &'_ str, i64, StreamRangeReply>(
+                    StorageKeyManager::create_key_event_stream(
+                        StorageKeyManager::get_key_event_part(event_type),
+                    ),
+                    "+",
+                    "-",
+                    1,
+                )
+                .await
+                .map(|stream_range_reply| {
+                    return match stream_range_reply.ids.into_iter().next() {
+                        Some(stream_id) => stream_id.id,
+                        None => "0-0".to_string(),
+                    };
+                }),
+        };
+
+        match cursor {
+            Ok(cursor_) => {
+                return Ok(cursor_);
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the last event".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn count(&self, event_type: EventType) -> Result<i64, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let quantity = match self.storage_mode {
+            StorageMode::Keys => redis::cmd("MGET")
+                .arg(StorageKeyManager::get_key_event_last_index(event_type))
+                .arg(StorageKeyManager::create_key_event_removed_quantity(
+                    StorageKeyManager::get_key_event_part(event_type),
+                ))
+                .query_async::<_, (Option<i64>, Option<i64>)>(connection)
+                .await
+                .map(|(last_index, removed_quantity)| {
+                    return last_index.unwrap_or(0) - removed_quantity.unwrap_or(0);
+                }),
+            StorageMode::Streams => {
+                connection
+                    .xlen::<'_, String, i64>(StorageKeyManager::create_key_event_stream(
+                        StorageKeyManager::get_key_event_part(event_type),
+                    ))
+                    .await
+            }
+        };
+
+        match quantity {
+            Ok(quantity_) => {
+                return Ok(quantity_);
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the event quantity".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn count_indexed(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+    ) -> Result<i64, Error> {
+        if !event_index.is_kept_for(event_type) {
+            return Ok(0);
+        }
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        match connection
+            .zcard::<'_, String, i64>(StorageKeyManager::create_key_event_index(
+                StorageKeyManager::get_key_event_part(event_type),
+                event_index,
+            ))
+            .await
+        {
+            Ok(quantity) => {
+                return Ok(quantity);
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the indexed event quantity".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_indexed_page(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        if !event_index.is_kept_for(event_type) || limit <= 0 {
+            return Ok(vec![]);
+        }
+
+        // The members of the index start with the padded checkpoint, so the page after a cursor starts
+        // after every member of its checkpoint.
+        let (is_offset, start) = match page_start {
+            EventPageStart::Offset(offset) => (true, offset.to_string()),
+            EventPageStart::After(cursor) => match EventCheckpoint::parse_cursor(cursor.as_str()) {
+                Some(checkpoint) => (
+                    false,
+                    format!(
+                        "[{:020}:{:010};",
+                        checkpoint.block_number, checkpoint.log_index
+                    ),
+                ),
+                None => {
+                    return Err(Error::from_cursor(cursor.as_str()));
+                }
+            },
+        };
+
+        let key_event_part = StorageKeyManager::get_key_event_part(event_type);
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        match Script::new(Self::INDEX_PAGE_SCRIPT)
+            .key(StorageKeyManager::create_key_event_index(
+                key_event_part,
+                event_index,
+            ))
+            .key(StorageKeyManager::create_key_event_stream(key_event_part))
+            .key(StorageKeyManager::create_key_event_stream_position(
+                key_event_part,
+            ))
+            .key(StorageKeyManager::create_key_event_stream_pending_record(
+                key_event_part,
+            ))
+            .arg(key_event_part)
+            .arg(self.storage_mode == StorageMode::Streams)
+            .arg(is_offset)
+            .arg(start)
+            .arg(limit)
+            .invoke_async::<_, Vec<String>>(connection)
+            .await
+        {
+            Ok(result) => {
+                return Ok(Self::create_event_entry_registry(result));
+            }
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the indexed events".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<Option<EventCheckpoint>, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let key_checkpoint = StorageKeyManager::create_key_checkpoint(
+            contract_address,
+            StorageKeyManager::get_key_event_part(event_type),
+        );
+
+        let data = match connection
+            .get::<'_, &'_ str, Option<String>>(&key_checkpoint)
+            .await
+        {
+            Ok(data_) => data_,
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the checkpoint".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        match data {
+            Some(data_) => match serde_json::from_str::<EventCheckpoint>(data_.as_str()) {
+                Ok(checkpoint) => {
+                    return Ok(Some(checkpoint));
+                }
+                Err(error) => {
+                    return Err(Error::Decoding {
+                        context: "Failed to parse the checkpoint".to_string(),
+                        source: Some(Box::from(error)),
+                    });
+                }
+            },
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn set_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        let checkpoint_data = match serde_json::to_string(&checkpoint) {
+            Ok(checkpoint_data_) => checkpoint_data_,
+            Err(error) => {
+                return Err(Error::Serialization {
+                    context: "Failed to serialize the checkpoint".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let key_checkpoint = StorageKeyManager::create_key_checkpoint(
+            contract_address,
+            StorageKeyManager::get_key_event_part(event_type),
+        );
+
+        if let Err(error) = connection
+            .set::<'_, String, String, ()>(key_checkpoint, checkpoint_data)
+            .await
+        {
+            return Err(Error::Redis {
+                context: "Failed to set the checkpoint".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn remove_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<(), Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let key_checkpoint = StorageKeyManager::create_key_checkpoint(
+            contract_address,
+            StorageKeyManager::get_key_event_part(event_type),
+        );
+
+        if let Err(error) = connection.del::<'_, String, ()>(key_checkpoint).await {
+            return Err(Error::Redis {
+                context: "Failed to remove the checkpoint".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn index_ownership(&self, token_ownership: TokenOwnership) -> Result<(), Error> {
+        let token_ownership_data = match serde_json::to_string(&token_ownership) {
+            Ok(token_ownership_data_) => token_ownership_data_,
+            Err(error) => {
+                return Err(Error::Serialization {
+                    context: "Failed to serialize the token ownership".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        if let Err(error) = Self::create_ownership_script(Self::OWNERSHIP_SCRIPT)
+            .key(StorageKeyManager::create_key_token_ownership(
+                token_ownership.collection,
+                token_ownership.token_id,
+            ))
+            .key(StorageKeyManager::create_key_token_ownership_position(
+                token_ownership.collection,
+                token_ownership.token_id,
+            ))
+            .key(StorageKeyManager::create_key_token_ownership_transfer(
+                token_ownership.collection,
+                token_ownership.token_id,
+            ))
+            .arg(token_ownership_data)
+            .arg(StorageKeyManager::create_event_stream_position(
+                token_ownership.block_number,
+                token_ownership.log_index,
+            ))
+            .arg(StorageKeyManager::create_owned_token(
+                token_ownership.collection,
+                token_ownership.token_id,
+            ))
+            .arg(StorageKeyManager::OWNED_TOKEN_PART)
+            .invoke_async::<_, i64>(connection)
+            .await
+        {
+            return Err(Error::Redis {
+                context: "Failed to index the token ownership".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn retract_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        if let Err(error) = Self::create_ownership_script(Self::OWNERSHIP_RETRACT_SCRIPT)
+            .key(StorageKeyManager::create_key_token_ownership(
+                collection, token_id,
+            ))
+            .key(StorageKeyManager::create_key_token_ownership_position(
+                collection, token_id,
+            ))
+            .key(StorageKeyManager::create_key_token_ownership_transfer(
+                collection, token_id,
+            ))
+            .arg(StorageKeyManager::create_event_stream_position(
+                checkpoint.block_number,
+                checkpoint.log_index,
+            ))
+            .arg(StorageKeyManager::create_owned_token(collection, token_id))
+            .arg(StorageKeyManager::OWNED_TOKEN_PART)
+            .invoke_async::<_, i64>(connection)
+            .await
+        {
+            return Err(Error::Redis {
+                context: "Failed to retract the token ownership".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn get_token_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<Option<TokenOwnership>, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let key_token_ownership =
+            StorageKeyManager::create_key_token_ownership(collection, token_id);
+
+        let data = match connection
+            .get::<'_, &'_ str, Option<String>>(&key_token_ownership)
+            .await
+        {
+            Ok(data_) => data_,
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the token ownership".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        match data {
+            Some(data_) => match serde_json::from_str::<TokenOwnership>(data_.as_str()) {
+                Ok(token_ownership) => {
+                    return Ok(Some(token_ownership));
+                }
+                Err(error) => {
+                    return Err(Error::Decoding {
+                        context: "Failed to parse the token ownership".to_string(),
+                        source: Some(Box::from(error)),
+                    });
+                }
+            },
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn get_owned_token_registry(&self, owner: Address) -> Result<Vec<OwnedToken>, Error> {
+        let mut pooled_connection = self.get_connection().await?;
+
+        let connection = &mut *pooled_connection;
+
+        let key_owned_token = StorageKeyManager::create_key_owned_token(owner);
+
+        let owned_token_registry = match connection
+            .smembers::<'_, &'_ str, Vec<String>>(&key_owned_token)
+            .await
+        {
+            Ok(owned_token_registry_) => owned_token_registry_,
+            Err(error) => {
+                return Err(Error::Redis {
+                    context: "Failed to get the owned tokens".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        let mut owned_token_registry_: Vec<OwnedToken> = vec![];
+
+        for owned_token in owned_token_registry.iter() {
+            match StorageKeyManager::parse_owned_token(owned_token.as_str()) {
+                Some(owned_token_) => {
+                    owned_token_registry_.push(owned_token_);
+                }
+                None => {
+                    return Err(Error::Decoding {
+                        context: format!("Failed to parse the owned token '{}'", owned_token),
+                        source: None,
+                    });
+                }
+            }
+        }
+
+        owned_token_registry_.sort_by(|owned_token, owned_token_| {
+            return (owned_token.collection, owned_token.token_id)
+                .cmp(&(owned_token_.collection, owned_token_.token_id));
+        });
+
+        return Ok(owned_token_registry_);
+    }
+
+    async fn subscribe(&self) -> Result<BoxStream<'static, EventNotification>, Error> {
+        let pubsub = EventChannelSubscriptionCreator::create(self.redis_url.as_str()).await?;
+
+        return Ok(pubsub
+            .into_on_message()
+            .filter_map(|message| async move {
+                return message.get_payload::<String>().ok().and_then(|payload| {
+                    serde_json::from_str::<EventNotification>(payload.as_str()).ok()
+                });
+            })
+            .boxed());
+    }
+}
+
+pub struct EventMigration {
+    pub event_type: EventType,
+    pub quantity: i64,
+    pub migrated_quantity: i64,
+}
+
+// Keeps the events of the process, which is enough to run without Redis.
+#[derive(Clone)]
+pub struct InMemoryEventStore {
+    state: Arc<Mutex<InMemoryEventState>>,
+    event_notification_sender: broadcast::Sender<EventNotification>,
+}
+
+impl InMemoryEventStore {
+    const EVENT_NOTIFICATION_CAPACITY: usize = 1024;
+
+    pub fn new() -> Self {
+        let (event_notification_sender, _) = broadcast::channel(Self::EVENT_NOTIFICATION_CAPACITY);
+
+        return Self {
+            state: Arc::new(Mutex::new(InMemoryEventState::default())),
+            event_notification_sender,
+        };
+    }
+
+    // The state is consistent after every change, so a panic while it is locked does not corrupt it.
+    fn lock_state(&self) -> MutexGuard<'_, InMemoryEventState> {
+        match self.state.lock() {
+            Ok(state) => {
+                return state;
+            }
+            Err(error) => {
+                return error.into_inner();
+            }
+        }
+    }
+
+    // The ownership is the latest transfer of the token.
+    fn update_token_ownership<'a>(state: &'a mut InMemoryEventState, token: (Address, U256)) -> () {
+        if let Some(token_ownership) = state.token_ownership_registry.remove(&token) {
+            if let Some(owned_token_registry) =
+                state.owned_token_registry.get_mut(&token_ownership.owner)
+            {
+                owned_token_registry.remove(&token);
+            }
+        }
+
+        let token_ownership = match state
+            .token_transfer_registry
+            .get(&token)
+            .and_then(|token_transfer_registry| token_transfer_registry.values().next_back())
+        {
+            Some(token_ownership_) => token_ownership_.clone(),
+            None => {
+                return ();
+            }
+        };
+
+        if !token_ownership.owner.is_zero() {
+            state
+                .owned_token_registry
+                .entry(token_ownership.owner)
+                .or_default()
+                .insert(token);
+        }
+
+        state
+            .token_ownership_registry
+            .insert(token, token_ownership);
+
+        return ();
+    }
+
+    // The cursor is the one-based index of the event.
+    fn create_event_notification<'a>(
+        event_type: EventType,
+        change: EventChange,
+        index: usize,
+        data: &'a str,
+    ) -> Result<EventNotification, Error> {
+        match serde_json::from_str::<Value>(data) {
+            Ok(record) => {
+                return Ok(EventNotification {
+                    event_type: event_type.get_name().to_string(),
+                    change,
+                    cursor: index.to_string(),
+                    record,
+                });
+            }
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: "Failed to parse the event record".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    // The records are searched, which is enough for the quantity of the events in memory.
+    fn get_indexed_event_registry<'a>(
+        state: &'a InMemoryEventState,
+        event_type: EventType,
+        event_index: EventIndex,
+    ) -> Vec<&'a InMemoryEvent> {
+        if !event_index.is_kept_for(event_type) {
+            return vec![];
+        }
+
+        let address = format!("{:?}", event_index.get_address());
+
+        let mut event_registry = match state.event_registry.get(&event_type) {
+            Some(event_registry_) => event_registry_
+                .iter()
+                .filter(|event| event.status != EventStatus::Removed)
+                .filter(
+                    |event| match serde_json::from_str::<Value>(event.data.as_str()) {
+                        Ok(record) => {
+                            return record["event"][event_index.get_field_name()].as_str()
+                                == Some(address.as_str());
+                        }
+                        Err(_) => {
+                            return false;
+                        }
+                    },
+                )
+                .collect::<Vec<&'a InMemoryEvent>>(),
+            None => vec![],
+        };
+
+        event_registry.sort_by_key(|event| event.checkpoint);
+
+        return event_registry;
+    }
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+        status: EventStatus,
+        data: String,
+    ) -> Result<bool, Error> {
+        let record = match serde_json::from_str::<Value>(data.as_str()) {
+            Ok(record_) => record_,
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: "Failed to parse the event record".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let mut event_notification: Option<EventNotification> = None;
+
+        let is_appended: bool;
+
+        {
+            let mut state = self.lock_state();
+
+            let event_registry = state.event_registry.entry(event_type).or_default();
+
+            let is_stored = event_registry.iter().any(|event| {
+                return event.status != EventStatus::Removed
+                    && event.transaction_hash == transaction_hash
+                    && event.checkpoint.log_index == checkpoint.log_index;
+            });
+
+            is_appended = !is_stored;
+
+            if is_appended {
+                event_registry.push(InMemoryEvent {
+                    checkpoint,
+                    transaction_hash,
+                    status,
+                    data,
+                });
+
+                event_notification = Some(EventNotification {
+                    event_type: event_type.get_name().to_string(),
+                    change: EventChange::Appended,
+                    cursor: event_registry.len().to_string(),
+                    record,
+                });
+            }
+
+            let checkpoint_ = state
+                .checkpoint_registry
+                .entry((contract_address, event_type))
+                .or_insert(checkpoint);
+
+            *checkpoint_ = cmp::max(*checkpoint_, checkpoint);
+        }
+
+        // Nobody may be subscribed.
+        if let Some(event_notification_) = event_notification {
+            let _ = self.event_notification_sender.send(event_notification_);
+        }
+
+        return Ok(is_appended);
+    }
+
+    async fn retract(
+        &self,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+    ) -> Result<(), Error> {
+        let event_notification: EventNotification;
+
+        {
+            let mut state = self.lock_state();
+
+            let (index, event) =
+                match state
+                    .event_registry
+                    .get_mut(&event_type)
+                    .and_then(|event_registry| {
+                        return event_registry.iter_mut().enumerate().find(|(_, event_)| {
+                            return event_.status != EventStatus::Removed
+                                && event_.transaction_hash == transaction_hash
+                                && event_.checkpoint.log_index == checkpoint.log_index;
+                        });
+                    }) {
+                    Some(event_) => event_,
+                    None => {
+                        return Ok(());
+                    }
+                };
+
+            event.data = EventStatusUpdater::update(event.data.as_str(), EventStatus::Removed)?;
+
+            event.status = EventStatus::Removed;
+
+            event_notification = Self::create_event_notification(
+                event_type,
+                EventChange::Retracted,
+                index + 1,
+                event.data.as_str(),
+            )?;
+        }
+
+        let _ = self.event_notification_sender.send(event_notification);
+
+        return Ok(());
+    }
+
+    async fn confirm(&self, event_type: EventType, final_block_number: u64) -> Result<(), Error> {
+        let mut event_notification_registry: Vec<EventNotification> = vec![];
+
+        {
+            let mut state = self.lock_state();
+
+            if let Some(event_registry) = state.event_registry.get_mut(&event_type) {
+                for (index, event) in event_registry.iter_mut().enumerate() {
+                    if event.status == EventStatus::Pending
+                        && event.checkpoint.block_number <= final_block_number
+                    {
+                        event.data =
+                            EventStatusUpdater::update(event.data.as_str(), EventStatus::Final)?;
+
+                        event.status = EventStatus::Final;
+
+                        event_notification_registry.push(Self::create_event_notification(
+                            event_type,
+                            EventChange::Confirmed,
+                            index + 1,
+                            event.data.as_str(),
+                        )?);
+                    }
+                }
+            }
+        }
+
+        for event_notification in event_notification_registry.into_iter() {
+            let _ = self.event_notification_sender.send(event_notification);
+        }
+
+        return Ok(());
+    }
+
+    async fn get_by_cursor<'a>(
+        &self,
+        event_type: EventType,
+        cursor: &'a str,
+    ) -> Result<Option<String>, Error> {
+        let index = match usize::from_str(cursor) {
+            Ok(index_) if index_ > 0 => index_,
+            _ => {
+                return Ok(None);
+            }
+        };
+
+        let state = self.lock_state();
+
+        match state
+            .event_registry
+            .get(&event_type)
+            .and_then(|event_registry| event_registry.get(index - 1))
+        {
+            Some(event) if event.status != EventStatus::Removed => {
+                return Ok(Some(event.data.clone()));
+            }
+            _ => {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn get_page(
+        &self,
+        event_type: EventType,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        let (after_index, skipped_quantity) = match page_start {
+            EventPageStart::Offset(offset) => (0, cmp::max(offset, 0) as usize),
+            EventPageStart::After(cursor) => match usize::from_str(cursor.as_str()) {
+                Ok(index) => (index, 0),
+                Err(_) => {
+                    return Err(Error::from_cursor(cursor.as_str()));
+                }
+            },
+        };
+
+        let state = self.lock_state();
+
+        match state.event_registry.get(&event_type) {
+            Some(event_registry) => {
+                return Ok(event_registry
+                    .iter()
+                    .enumerate()
+                    .skip(after_index)
+                    .filter(|(_, event)| event.status != EventStatus::Removed)
+                    .skip(skipped_quantity)
+                    .take(cmp::max(limit, 0) as usize)
+                    .map(|(index, event)| EventEntry {
+                        cursor: (index + 1).to_string(),
+                        data: event.data.clone(),
+                    })
+                    .collect::<Vec<EventEntry>>());
+            }
+            None => {
+                return Ok(vec![]);
+            }
+        }
+    }
+
+    async fn get_last_cursor(&self, event_type: EventType) -> Result<String, Error> {
+        let state = self.lock_state();
+
+        let quantity = state
+            .event_registry
+            .get(&event_type)
+            .map(|event_registry| event_registry.len())
+            .unwrap_or(0);
+
+        return Ok(quantity.to_string());
+    }
+
+    async fn count(&self, event_type: EventType) -> Result<i64, Error> {
+        let state = self.lock_state();
+
+        match state.event_registry.get(&event_type) {
+            Some(event_registry) => {
+                return Ok(event_registry
+                    .iter()
+                    .filter(|event| event.status != EventStatus::Removed)
+                    .count() as i64);
+            }
+            None => {
+                return Ok(0);
+            }
+        }
+    }
+
+    async fn count_indexed(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+    ) -> Result<i64, Error> {
+        let state = self.lock_state();
+
+        return Ok(Self::get_indexed_event_registry(&state, event_type, event_index).len() as i64);
+    }
+
+    async fn get_indexed_page(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        let (after_checkpoint, skipped_quantity) = match page_start {
+            EventPageStart::Offset(offset) => (None, cmp::max(offset, 0) as usize),
+            EventPageStart::After(cursor) => match EventCheckpoint::parse_cursor(cursor.as_str()) {
+                Some(checkpoint) => (Some(checkpoint), 0),
+                None => {
+                    return Err(Error::from_cursor(cursor.as_str()));
+                }
+            },
+        };
+
+        let state = self.lock_state();
+
+        return Ok(
+            Self::get_indexed_event_registry(&state, event_type, event_index)
+                .into_iter()
+                .filter(|event| Some(event.checkpoint) > after_checkpoint)
+                .skip(skipped_quantity)
+                .take(cmp::max(limit, 0) as usize)
+                .map(|event| EventEntry {
+                    cursor: event.checkpoint.format_cursor(),
+                    data: event.data.clone(),
+                })
+                .collect::<Vec<EventEntry>>(),
+        );
+    }
+
+    async fn get_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<Option<EventCheckpoint>, Error> {
+        let state = self.lock_state();
+
+        return Ok(state
+            .checkpoint_registry
+            .get(&(contract_address, event_type))
+            .copied());
+    }
+
+    async fn set_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        let mut state = self.lock_state();
+
+        state
+            .checkpoint_registry
+            .insert((contract_address, event_type), checkpoint);
+
+        return Ok(());
+    }
+
+    async fn remove_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<(), Error> {
+        let mut state = self.lock_state();
+
+        state
+            .checkpoint_registry
+            .remove(&(contract_address, event_type));
+
+        return Ok(());
+    }
+
+    async fn index_ownership(&self, token_ownership: TokenOwnership) -> Result<(), Error> {
+        let mut state = self.lock_state();
+
+        let token = (token_ownership.collection, token_ownership.token_id);
+
+        state
+            .token_transfer_registry
+            .entry(token)
+            .or_default()
+            .insert(
+                EventCheckpoint {
+                    block_number: token_ownership.block_number,
+                    log_index: token_ownership.log_index,
+                },
+                token_ownership,
+            );
+
+        Self::update_token_ownership(&mut state, token);
+
+        return Ok(());
+    }
+
+    async fn retract_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        let mut state = self.lock_state();
+
+        let token = (collection, token_id);
+
+        if let Some(token_transfer_registry) = state.token_transfer_registry.get_mut(&token) {
+            if token_transfer_registry.remove(&checkpoint).is_none() {
+                return Ok(());
+            }
+        }
+
+        Self::update_token_ownership(&mut state, token);
+
+        return Ok(());
+    }
+
+    async fn get_token_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<Option<TokenOwnership>, Error> {
+        let state = self.lock_state();
+
+        return Ok(state
+            .token_ownership_registry
+            .get(&(collection, token_id))
+            .cloned());
+    }
+
+    async fn get_owned_token_registry(&self, owner: Address) -> Result<Vec<OwnedToken>, Error> {
+        let state = self.lock_state();
+
+        match state.owned_token_registry.get(&owner) {
+            Some(owned_token_registry) => {
+                return Ok(owned_token_registry
+                    .iter()
+                    .map(|(collection, token_id)| OwnedToken {
+                        collection: *collection,
+                        token_id: *token_id,
+                    })
+                    .collect::<Vec<OwnedToken>>());
+            }
+            None => {
+                return Ok(vec![]);
+            }
+        }
+    }
+
+    async fn subscribe(&self) -> Result<BoxStream<'static, EventNotification>, Error> {
+        let event_notification_receiver = self.event_notification_sender.subscribe();
+
+        return Ok(stream::unfold(
+            event_notification_receiver,
+            |mut event_notification_receiver_| async move {
+                match event_notification_receiver_.recv().await {
+                    Ok(event_notification) => {
+                        return Some((event_notification, event_notification_receiver_));
+                    }
+                    // The subscriber that falls behind has missed events, so its stream ends and it resumes
+                    // from its cursors.
+                    Err(RecvError::Lagged(_) | RecvError::Closed) => {
+                        return None;
+                    }
+                }
+            },
+        )
+        .boxed());
+    }
+}
+
+#[derive(Default)]
+struct InMemoryEventState {
+    event_registry: HashMap<EventType, Vec<InMemoryEvent>>,
+    checkpoint_registry: HashMap<(Address, EventType), EventCheckpoint>,
+    token_ownership_registry: HashMap<(Address, U256), TokenOwnership>,
+    token_transfer_registry: HashMap<(Address, U256), BTreeMap<EventCheckpoint, TokenOwnership>>,
+    owned_token_registry: HashMap<Address, BTreeSet<(Address, U256)>>,
+}
+
+struct InMemoryEvent {
+    checkpoint: EventCheckpoint,
+    transaction_hash: H256,
+    status: EventStatus,
+    data: String,
+}
+
+// Keeps the events in PostgreSQL or SQLite. The 'events' table serves the indexes of the API, and the
+// 'collections', 'mints', 'transfers' and 'tokens' tables are kept for SQL queries.
+#[derive(Clone)]
+pub struct SqlEventStore {
+    connection_pool: AnyPool,
+}
+
+impl SqlEventStore {
+    const EVENT_APPEND_ATTEMPT_QUANTITY: u32 = 5;
+    const EVENT_POLLING_INTERVAL: Duration = Duration::from_millis(500);
+    const EVENT_POLLING_QUANTITY: i64 = 100;
+
+    pub fn new(connection_pool: AnyPool) -> Self {
+        return Self { connection_pool };
+    }
+
+    fn get_status_name(status: EventStatus) -> &'static str {
+        match status {
+            EventStatus::Pending => {
+                return "pending";
+            }
+            EventStatus::Final => {
+                return "final";
+            }
+            EventStatus::Removed => {
+                return "removed";
+            }
+        }
+    }
+
+    // The table that keeps the event in the normalized form, if there is one.
+    fn get_table_name(event_type: EventType) -> Option<&'static str> {
+        match event_type {
+            EventType::CollectionCreated => {
+                return Some("collections");
+            }
+            EventType::TokenMinted => {
+                return Some("mints");
+            }
+            EventType::Transfer => {
+                return Some("transfers");
+            }
+            EventType::Approval | EventType::ApprovalForAll => {
+                return None;
+            }
+        }
+    }
+
+    fn parse_sql_entity(event_type: EventType, data: &'_ str) -> Result<SqlEntity, Error> {
+        let sql_entity = match event_type {
+            EventType::CollectionCreated => {
+                serde_json::from_str::<EventRecord<CollectionCreatedEvent>>(data)
+                    .map(|record| SqlEntity::Collection(record.event))
+            }
+            EventType::TokenMinted => serde_json::from_str::<EventRecord<TokenMintedEvent>>(data)
+                .map(|record| SqlEntity::Mint(record.event)),
+            EventType::Transfer => serde_json::from_str::<EventRecord<TransferEvent>>(data)
+                .map(|record| SqlEntity::Transfer(record.event)),
+            EventType::Approval | EventType::ApprovalForAll => Ok(SqlEntity::Nothing),
+        };
+
+        match sql_entity {
+            Ok(sql_entity_) => {
+                return Ok(sql_entity_);
+            }
+            Err(error) => {
+                return Err(Error::Decoding {
+                    context: "Failed to parse the event record".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    fn parse_address<'a>(value: &'a str) -> Result<Address, SqlxError> {
+        match Address::from_str(value) {
+            Ok(address) => {
+                return Ok(address);
+            }
+            Err(error) => {
+                return Err(SqlxError::Decode(Box::from(error)));
+            }
+        }
+    }
+
+    fn parse_token_id<'a>(value: &'a str) -> Result<U256, SqlxError> {
+        match U256::from_dec_str(value) {
+            Ok(token_id) => {
+                return Ok(token_id);
+            }
+            Err(error) => {
+                return Err(SqlxError::Decode(Box::from(format!("{:?}", error))));
+            }
+        }
+    }
+
+    // The log index of a checkpoint may be the greatest u64, so it is kept with the same bits in an i64.
+    async fn get_checkpoint_in_transaction<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<Option<EventCheckpoint>, SqlxError> {
+        let row = sqlx::query(
+            "SELECT block_number, log_index FROM checkpoints WHERE contract_address = $1 AND event_type = $2",
+        )
+        .bind(format!("{:?}", contract_address))
+        .bind(event_type.get_name())
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        match row {
+            Some(row_) => {
+                return Ok(Some(EventCheckpoint {
+                    block_number: row_.try_get::<i64, _>("block_number")? as u64,
+                    log_index: row_.try_get::<i64, _>("log_index")? as u64,
+                }));
+            }
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn set_checkpoint_in_transaction<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO checkpoints (contract_address, event_type, block_number, log_index) VALUES ($1, $2, $3, $4) \
+            ON CONFLICT (contract_address, event_type) DO UPDATE SET block_number = excluded.block_number, log_index = excluded.log_index",
+        )
+        .bind(format!("{:?}", contract_address))
+        .bind(event_type.get_name())
+        .bind(checkpoint.block_number as i64)
+        .bind(checkpoint.log_index as i64)
+        .execute(&mut *transaction)
+        .await?;
+
+        return Ok(());
+    }
+
+    // Every change takes the next sequence, so that the subscribers read the changes in order.
+    async fn add_event_change<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        event_type: EventType,
+        position: i64,
+        change: EventChange,
+        record: &'a str,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE event_sequence SET value = value + 1")
+            .execute(&mut *transaction)
+            .await?;
+
+        let sequence = sqlx::query("SELECT value FROM event_sequence")
+            .fetch_one(&mut *transaction)
+            .await?
+            .try_get::<i64, _>("value")?;
+
+        sqlx::query(
+            "INSERT INTO event_changes (sequence, event_type, position, change, record) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(sequence)
+        .bind(event_type.get_name())
+        .bind(position)
+        .bind(change.get_name())
+        .bind(record)
+        .execute(&mut *transaction)
+        .await?;
+
+        return Ok(());
+    }
+
+    // The transaction that conflicts with a concurrent writer can be run again: the serialization failures
+    // and deadlocks of PostgreSQL, and the busy and locked database of SQLite.
+    fn is_conflict<'a>(&'a self, error: &'a SqlxError) -> bool {
+        let code = match *error {
+            SqlxError::Database(ref database_error) => match database_error.code() {
+                Some(code_) => code_.to_string(),
+                None => {
+                    return false;
+                }
+            },
+            _ => {
+                return false;
+            }
+        };
+
+        match self.connection_pool.any_kind() {
+            AnyKind::Postgres => {
+                return code == "40001" || code == "40P01";
+            }
+            AnyKind::Sqlite => {
+                // The extended result codes keep the primary one in the lowest byte.
+                return matches!(i32::from_str(code.as_str()), Ok(code_) if code_ & 0xff == 5 || code_ & 0xff == 6);
+            }
+        }
+    }
+
+    // The positions are taken from the row of the event type, which is locked until the transaction ends,
+    // so that the concurrent writers never take the same one.
+    async fn allocate_position<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        event_type: EventType,
+    ) -> Result<i64, SqlxError> {
+        return sqlx::query(
+            "INSERT INTO event_positions (event_type, value) VALUES ($1, 1) \
+            ON CONFLICT (event_type) DO UPDATE SET value = event_positions.value + 1 RETURNING value",
+        )
+        .bind(event_type.get_name())
+        .fetch_one(&mut *transaction)
+        .await?
+        .try_get::<i64, _>("value");
+    }
+
+    // The row is still locked, so the position that is not used is given back without leaving a gap.
+    async fn release_position<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        event_type: EventType,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE event_positions SET value = value - 1 WHERE event_type = $1")
+            .bind(event_type.get_name())
+            .execute(&mut *transaction)
+            .await?;
+
+        return Ok(());
+    }
+
+    async fn append_event<'a>(
+        &'a self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+        status: EventStatus,
+        data: &'a str,
+        sql_entity: SqlEntity,
+    ) -> Result<bool, SqlxError> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let transaction_hash_ = format!("{:?}", transaction_hash);
+
+        let status_ = Self::get_status_name(status);
+
+        // The position is taken first, so that SQLite locks the database for writing before anything is read
+        // and the concurrent transactions wait for each other instead of failing to upgrade their locks.
+        let position = Self::allocate_position(&mut transaction, event_type).await?;
+
+        // The event that is stored already is not inserted.
+        let is_appended = sqlx::query(
+            "INSERT INTO events (event_type, position, tx_hash, log_index, block_number, status, record) \
+            VALUES ($1, $2, $3, $4, $5, $6, $7) \
+            ON CONFLICT (event_type, tx_hash, log_index) WHERE status <> 'removed' DO NOTHING",
+        )
+        .bind(event_type.get_name())
+        .bind(position)
+        .bind(transaction_hash_.as_str())
+        .bind(checkpoint.log_index as i64)
+        .bind(checkpoint.block_number as i64)
+        .bind(status_)
+        .bind(data)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if !is_appended {
+            Self::release_position(&mut transaction, event_type).await?;
+        }
+
+        if is_appended {
+            match sql_entity {
+                SqlEntity::Collection(event) => {
+                    sqlx::query(
+                        "INSERT INTO collections (address, name, symbol, block_number, tx_hash, log_index, status) \
+                        VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (address) DO NOTHING",
+                    )
+                    .bind(format!("{:?}", event.collection))
+                    .bind(event.name)
+                    .bind(event.symbol)
+                    .bind(event.block_number as i64)
+                    .bind(transaction_hash_.as_str())
+                    .bind(event.log_index as i64)
+                    .bind(status_)
+                    .execute(&mut transaction)
+                    .await?;
+                }
+                SqlEntity::Mint(event) => {
+                    sqlx::query(
+                        "INSERT INTO mints (tx_hash, log_index, collection, recipient, token_id, token_uri, block_number, status) \
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT (tx_hash, log_index) DO NOTHING",
+                    )
+                    .bind(transaction_hash_.as_str())
+                    .bind(event.log_index as i64)
+                    .bind(format!("{:?}", event.collection))
+                    .bind(format!("{:?}", event.recipient))
+                    .bind(event.token_id.to_string())
+                    .bind(event.token_uri)
+                    .bind(event.block_number as i64)
+                    .bind(status_)
+                    .execute(&mut transaction)
+                    .await?;
+                }
+                SqlEntity::Transfer(event) => {
+                    sqlx::query(
+                        "INSERT INTO transfers (tx_hash, log_index, collection, from_address, to_address, token_id, block_number, status) \
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT (tx_hash, log_index) DO NOTHING",
+                    )
+                    .bind(transaction_hash_.as_str())
+                    .bind(event.log_index as i64)
+                    .bind(format!("{:?}", event.collection))
+                    .bind(format!("{:?}", event.from))
+                    .bind(format!("{:?}", event.to))
+                    .bind(event.token_id.to_string())
+                    .bind(event.block_number as i64)
+                    .bind(status_)
+                    .execute(&mut transaction)
+                    .await?;
+                }
+                SqlEntity::Nothing => {}
+            }
+
+            Self::add_event_change(
+                &mut transaction,
+                event_type,
+                position,
+                EventChange::Appended,
+                data,
+            )
+            .await?;
+        }
+
+        let is_checkpoint_moved = match Self::get_checkpoint_in_transaction(
+            &mut transaction,
+            contract_address,
+            event_type,
+        )
+        .await?
+        {
+            Some(checkpoint_) => checkpoint_ < checkpoint,
+            None => true,
+        };
+
+        if is_checkpoint_moved {
+            Self::set_checkpoint_in_transaction(
+                &mut transaction,
+                contract_address,
+                event_type,
+                checkpoint,
+            )
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        return Ok(is_appended);
+    }
+
+    async fn retract_event<'a>(
+        &'a self,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+    ) -> Result<(), SqlxError> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let transaction_hash_ = format!("{:?}", transaction_hash);
+
+        let row = sqlx::query(
+            "SELECT position, record FROM events WHERE event_type = $1 AND tx_hash = $2 AND log_index = $3 AND status <> 'removed'",
+        )
+        .bind(event_type.get_name())
+        .bind(transaction_hash_.as_str())
+        .bind(checkpoint.log_index as i64)
+        .fetch_optional(&mut transaction)
+        .await?;
+
+        let (position, record) = match row {
+            Some(row_) => (
+                row_.try_get::<i64, _>("position")?,
+                row_.try_get::<String, _>("record")?,
+            ),
+            None => {
+                return Ok(());
+            }
+        };
+
+        let record_ = match EventStatusUpdater::update(record.as_str(), EventStatus::Removed) {
+            Ok(record__) => record__,
+            Err(error) => {
+                return Err(SqlxError::Decode(Box::from(error)));
+            }
+        };
+
+        // The position of the retracted event is kept, so that the positions never change.
+        sqlx::query(
+            "UPDATE events SET status = 'removed', record = $1 WHERE event_type = $2 AND position = $3",
+        )
+        .bind(record_.as_str())
+        .bind(event_type.get_name())
+        .bind(position)
+        .execute(&mut transaction)
+        .await?;
+
+        Self::add_event_change(
+            &mut transaction,
+            event_type,
+            position,
+            EventChange::Retracted,
+            record_.as_str(),
+        )
+        .await?;
+
+        if let Some(table_name) = Self::get_table_name(event_type) {
+            sqlx::query(
+                format!(
+                    "DELETE FROM {} WHERE tx_hash = $1 AND log_index = $2",
+                    table_name
+                )
+                .as_str(),
+            )
+            .bind(transaction_hash_.as_str())
+            .bind(checkpoint.log_index as i64)
+            .execute(&mut transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        return Ok(());
+    }
+
+    async fn confirm_event<'a>(
+        &'a self,
+        event_type: EventType,
+        final_block_number: u64,
+    ) -> Result<(), SqlxError> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let row_registry = sqlx::query(
+            "SELECT position, record FROM events WHERE event_type = $1 AND status = 'pending' AND block_number <= $2 \
+            ORDER BY position",
+        )
+        .bind(event_type.get_name())
+        .bind(final_block_number as i64)
+        .fetch_all(&mut transaction)
+        .await?;
+
+        for row in row_registry.into_iter() {
+            let record = match EventStatusUpdater::update(
+                row.try_get::<String, _>("record")?.as_str(),
+                EventStatus::Final,
+            ) {
+                Ok(record_) => record_,
+                Err(error) => {
+                    return Err(SqlxError::Decode(Box::from(error)));
+                }
+            };
+
+            let position = row.try_get::<i64, _>("position")?;
+
+            sqlx::query(
+                "UPDATE events SET status = 'final', record = $1 WHERE event_type = $2 AND position = $3",
+            )
+            .bind(record.as_str())
+            .bind(event_type.get_name())
+            .bind(position)
+            .execute(&mut transaction)
+            .await?;
+
+            Self::add_event_change(
+                &mut transaction,
+                event_type,
+                position,
+                EventChange::Confirmed,
+                record.as_str(),
+            )
+            .await?;
+        }
+
+        if let Some(table_name) = Self::get_table_name(event_type) {
+            sqlx::query(
+                format!(
+                    "UPDATE {} SET status = 'final' WHERE status = 'pending' AND block_number <= $1",
+                    table_name
+                )
+                .as_str(),
+            )
+            .bind(final_block_number as i64)
+            .execute(&mut transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        return Ok(());
+    }
+
+    async fn get_event_page<'a>(
+        &'a self,
+        event_type: EventType,
+        after_position: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, SqlxError> {
+        let row_registry = sqlx::query(
+            "SELECT position, record FROM events WHERE event_type = $1 AND position > $2 AND status <> 'removed' \
+            ORDER BY position LIMIT $3 OFFSET $4",
+        )
+        .bind(event_type.get_name())
+        .bind(after_position)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let mut event_entry_registry: Vec<EventEntry> = vec![];
+
+        for row in row_registry.into_iter() {
+            event_entry_registry.push(EventEntry {
+                cursor: row.try_get::<i64, _>("position")?.to_string(),
+                data: row.try_get::<String, _>("record")?,
+            });
+        }
+
+        return Ok(event_entry_registry);
+    }
+
+    async fn get_token_ownership_in_transaction<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<Option<TokenOwnership>, SqlxError> {
+        let row = sqlx::query(
+            "SELECT owner, block_number, log_index FROM tokens WHERE collection = $1 AND token_id = $2",
+        )
+        .bind(format!("{:?}", collection))
+        .bind(token_id.to_string())
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        match row {
+            Some(row_) => {
+                return Ok(Some(TokenOwnership {
+                    collection,
+                    token_id,
+                    owner: Self::parse_address(row_.try_get::<String, _>("owner")?.as_str())?,
+                    block_number: row_.try_get::<i64, _>("block_number")? as u64,
+                    log_index: row_.try_get::<i64, _>("log_index")? as u64,
+                }));
+            }
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn set_token_ownership_in_transaction<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        token_ownership: &'a TokenOwnership,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO tokens (collection, token_id, owner, block_number, log_index) VALUES ($1, $2, $3, $4, $5) \
+            ON CONFLICT (collection, token_id) DO UPDATE SET owner = excluded.owner, block_number = excluded.block_number, log_index = excluded.log_index",
+        )
+        .bind(format!("{:?}", token_ownership.collection))
+        .bind(token_ownership.token_id.to_string())
+        .bind(format!("{:?}", token_ownership.owner))
+        .bind(token_ownership.block_number as i64)
+        .bind(token_ownership.log_index as i64)
+        .execute(&mut *transaction)
+        .await?;
+
+        return Ok(());
+    }
+
+    // The ownership is the latest transfer of the token, or nothing if it has none.
+    async fn update_token_ownership_in_transaction<'a>(
+        transaction: &'a mut Transaction<'static, Any>,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<(), SqlxError> {
+        let row = sqlx::query(
+            "SELECT owner, block_number, log_index FROM token_transfers WHERE collection = $1 AND token_id = $2 \
+            ORDER BY block_number DESC, log_index DESC LIMIT 1",
+        )
+        .bind(format!("{:?}", collection))
+        .bind(token_id.to_string())
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        match row {
+            Some(row_) => {
+                let token_ownership = TokenOwnership {
+                    collection,
+                    token_id,
+                    owner: Self::parse_address(row_.try_get::<String, _>("owner")?.as_str())?,
+                    block_number: row_.try_get::<i64, _>("block_number")? as u64,
+                    log_index: row_.try_get::<i64, _>("log_index")? as u64,
+                };
+
+                Self::set_token_ownership_in_transaction(transaction, &token_ownership).await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM tokens WHERE collection = $1 AND token_id = $2")
+                    .bind(format!("{:?}", collection))
+                    .bind(token_id.to_string())
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    async fn index_token_ownership<'a>(
+        &'a self,
+        token_ownership: TokenOwnership,
+    ) -> Result<(), SqlxError> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO token_transfers (collection, token_id, owner, block_number, log_index) VALUES ($1, $2, $3, $4, $5) \
+            ON CONFLICT (collection, token_id, block_number, log_index) DO NOTHING",
+        )
+        .bind(format!("{:?}", token_ownership.collection))
+        .bind(token_ownership.token_id.to_string())
+        .bind(format!("{:?}", token_ownership.owner))
+        .bind(token_ownership.block_number as i64)
+        .bind(token_ownership.log_index as i64)
+        .execute(&mut transaction)
+        .await?;
+
+        Self::update_token_ownership_in_transaction(
+            &mut transaction,
+            token_ownership.collection,
+            token_ownership.token_id,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        return Ok(());
+    }
+
+    async fn retract_token_ownership<'a>(
+        &'a self,
+        collection: Address,
+        token_id: U256,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), SqlxError> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let query_result = sqlx::query(
+            "DELETE FROM token_transfers WHERE collection = $1 AND token_id = $2 AND block_number = $3 AND log_index = $4",
+        )
+        .bind(format!("{:?}", collection))
+        .bind(token_id.to_string())
+        .bind(checkpoint.block_number as i64)
+        .bind(checkpoint.log_index as i64)
+        .execute(&mut transaction)
+        .await?;
+
+        if query_result.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        Self::update_token_ownership_in_transaction(&mut transaction, collection, token_id).await?;
+
+        transaction.commit().await?;
+
+        return Ok(());
+    }
+
+    async fn get_owned_tokens<'a>(&'a self, owner: Address) -> Result<Vec<OwnedToken>, SqlxError> {
+        // The burned tokens are kept with the zero owner.
+        if owner.is_zero() {
+            return Ok(vec![]);
+        }
+
+        let row_registry = sqlx::query("SELECT collection, token_id FROM tokens WHERE owner = $1")
+            .bind(format!("{:?}", owner))
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        let mut owned_token_registry: Vec<(Address, U256)> = vec![];
+
+        for row in row_registry.into_iter() {
+            owned_token_registry.push((
+                Self::parse_address(row.try_get::<String, _>("collection")?.as_str())?,
+                Self::parse_token_id(row.try_get::<String, _>("token_id")?.as_str())?,
+            ));
+        }
+
+        owned_token_registry.sort();
+
+        return Ok(owned_token_registry
+            .into_iter()
+            .map(|(collection, token_id)| OwnedToken {
+                collection,
+                token_id,
+            })
+            .collect::<Vec<OwnedToken>>());
+    }
+
+    // The column of the index has the name of its field, except the address of the collection.
+    fn get_column_name<'a>(table_name: &'a str, event_index: EventIndex) -> &'static str {
+        match (table_name, event_index) {
+            ("collections", EventIndex::Collection(_)) => {
+                return "address";
+            }
+            _ => {
+                return event_index.get_field_name();
+            }
+        }
+    }
+
+    async fn count_indexed_event<'a>(
+        &'a self,
+        table_name: &'a str,
+        event_index: EventIndex,
+    ) -> Result<i64, SqlxError> {
+        return sqlx::query(
+            format!(
+                "SELECT COUNT(*) AS quantity FROM {} WHERE {} = $1",
+                table_name,
+                Self::get_column_name(table_name, event_index)
+            )
+            .as_str(),
+        )
+        .bind(format!("{:?}", event_index.get_address()))
+        .fetch_one(&self.connection_pool)
+        .await?
+        .try_get::<i64, _>("quantity");
+    }
+
+    // The events are ordered by their checkpoints, so the page after a cursor starts after its checkpoint.
+    async fn get_indexed_event_page<'a>(
+        &'a self,
+        event_type: EventType,
+        table_name: &'a str,
+        event_index: EventIndex,
+        after_checkpoint: Option<EventCheckpoint>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, SqlxError> {
+        let (after_block_number, after_log_index) = match after_checkpoint {
+            Some(checkpoint) => (checkpoint.block_number as i64, checkpoint.log_index as i64),
+            None => (-1, -1),
+        };
+
+        let row_registry = sqlx::query(
+            format!(
+                "SELECT {0}.block_number, {0}.log_index, events.record FROM {0} JOIN events ON events.event_type = $1 \
+                AND events.tx_hash = {0}.tx_hash AND events.log_index = {0}.log_index AND events.status <> 'removed' \
+                WHERE {0}.{1} = $2 AND ({0}.block_number > $3 OR ({0}.block_number = $3 AND {0}.log_index > $4)) \
+                ORDER BY {0}.block_number, {0}.log_index LIMIT $5 OFFSET $6",
+                table_name,
+                Self::get_column_name(table_name, event_index)
+            )
+            .as_str(),
+        )
+        .bind(event_type.get_name())
+        .bind(format!("{:?}", event_index.get_address()))
+        .bind(after_block_number)
+        .bind(after_log_index)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        let mut event_entry_registry: Vec<EventEntry> = vec![];
+
+        for row in row_registry.into_iter() {
+            let checkpoint = EventCheckpoint {
+                block_number: row.try_get::<i64, _>("block_number")? as u64,
+                log_index: row.try_get::<i64, _>("log_index")? as u64,
+            };
+
+            event_entry_registry.push(EventEntry {
+                cursor: checkpoint.format_cursor(),
+                data: row.try_get::<String, _>("record")?,
+            });
+        }
+
+        return Ok(event_entry_registry);
+    }
+
+    async fn get_event_sequence<'a>(connection_pool: &'a AnyPool) -> Result<i64, SqlxError> {
+        return sqlx::query("SELECT value FROM event_sequence")
+            .fetch_one(connection_pool)
+            .await?
+            .try_get::<i64, _>("value");
+    }
+
+    async fn get_event_notification_registry<'a>(
+        connection_pool: &'a AnyPool,
+        sequence: i64,
+    ) -> Result<Vec<(i64, EventNotification)>, SqlxError> {
+        let row_registry = sqlx::query(
+            "SELECT sequence, event_type, position, change, record FROM event_changes WHERE sequence > $1 ORDER BY sequence LIMIT $2",
+        )
+        .bind(sequence)
+        .bind(Self::EVENT_POLLING_QUANTITY)
+        .fetch_all(connection_pool)
+        .await?;
+
+        let mut event_notification_registry: Vec<(i64, EventNotification)> = vec![];
+
+        for row in row_registry.into_iter() {
+            let record =
+                match serde_json::from_str::<Value>(row.try_get::<String, _>("record")?.as_str()) {
+                    Ok(record_) => record_,
+                    Err(error) => {
+                        return Err(SqlxError::Decode(Box::from(error)));
+                    }
+                };
+
+            let change = match EventChange::from_name(row.try_get::<String, _>("change")?.as_str())
+            {
+                Some(change_) => change_,
+                None => {
+                    return Err(SqlxError::Decode(Box::from("The event change is unknown")));
+                }
+            };
+
+            event_notification_registry.push((
+                row.try_get::<i64, _>("sequence")?,
+                EventNotification {
+                    event_type: row.try_get::<String, _>("event_type")?,
+                    change,
+                    cursor: row.try_get::<i64, _>("position")?.to_string(),
+                    record,
+                },
+            ));
+        }
+
+        return Ok(event_notification_registry);
+    }
+}
+
+#[async_trait]
+impl EventStore for SqlEventStore {
+    async fn append(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+        status: EventStatus,
+        data: String,
+    ) -> Result<bool, Error> {
+        let mut attempt: u32 = 1;
+
+        loop {
+            let sql_entity = Self::parse_sql_entity(event_type, data.as_str())?;
+
+            match self
+                .append_event(
+                    contract_address,
+                    event_type,
+                    checkpoint,
+                    transaction_hash,
+                    status,
+                    data.as_str(),
+                    sql_entity,
+                )
+                .await
+            {
+                Ok(is_appended) => {
+                    return Ok(is_appended);
+                }
+                Err(error) => {
+                    if attempt < Self::EVENT_APPEND_ATTEMPT_QUANTITY && self.is_conflict(&error) {
+                        attempt += 1;
+
+                        continue;
+                    }
+
+                    return Err(Error::Sql {
+                        context: "Failed to store the event".to_string(),
+                        source: error,
+                    });
+                }
+            }
+        }
+    }
+
+    async fn retract(
+        &self,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+        transaction_hash: H256,
+    ) -> Result<(), Error> {
+        if let Err(error) = self
+            .retract_event(event_type, checkpoint, transaction_hash)
+            .await
+        {
+            return Err(Error::Sql {
+                context: "Failed to retract the event".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn confirm(&self, event_type: EventType, final_block_number: u64) -> Result<(), Error> {
+        if let Err(error) = self.confirm_event(event_type, final_block_number).await {
+            return Err(Error::Sql {
+                context: "Failed to confirm the pending events".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn get_by_cursor<'a>(
+        &self,
+        event_type: EventType,
+        cursor: &'a str,
+    ) -> Result<Option<String>, Error> {
+        let position = match i64::from_str(cursor) {
+            Ok(position_) => position_,
+            Err(_) => {
+                return Ok(None);
+            }
+        };
+
+        let data = sqlx::query(
+            "SELECT record FROM events WHERE event_type = $1 AND position = $2 AND status <> 'removed'",
+        )
+        .bind(event_type.get_name())
+        .bind(position)
+        .fetch_optional(&self.connection_pool)
+        .await
+        .and_then(|row| {
+            return row
+                .map(|row_| row_.try_get::<String, _>("record"))
+                .transpose();
+        });
+
+        match data {
+            Ok(data_) => {
+                return Ok(data_);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the event".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_page(
+        &self,
+        event_type: EventType,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        let (after_position, offset) = match page_start {
+            EventPageStart::Offset(offset_) => (0, offset_),
+            EventPageStart::After(cursor) => match i64::from_str(cursor.as_str()) {
+                Ok(position) => (position, 0),
+                Err(_) => {
+                    return Err(Error::from_cursor(cursor.as_str()));
+                }
+            },
+        };
+
+        match self
+            .get_event_page(event_type, after_position, offset, limit)
+            .await
+        {
+            Ok(event_entry_registry) => {
+                return Ok(event_entry_registry);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the events".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_last_cursor(&self, event_type: EventType) -> Result<String, Error> {
+        let position = sqlx::query(
+            "SELECT COALESCE(MAX(position), 0) AS position FROM events WHERE event_type = $1",
+        )
+        .bind(event_type.get_name())
+        .fetch_one(&self.connection_pool)
+        .await
+        .and_then(|row| row.try_get::<i64, _>("position"));
+
+        match position {
+            Ok(position_) => {
+                return Ok(position_.to_string());
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the last event".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn count(&self, event_type: EventType) -> Result<i64, Error> {
+        let quantity = sqlx::query(
+            "SELECT COUNT(*) AS quantity FROM events WHERE event_type = $1 AND status <> 'removed'",
+        )
+        .bind(event_type.get_name())
+        .fetch_one(&self.connection_pool)
+        .await
+        .and_then(|row| row.try_get::<i64, _>("quantity"));
+
+        match quantity {
+            Ok(quantity_) => {
+                return Ok(quantity_);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the event quantity".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn count_indexed(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+    ) -> Result<i64, Error> {
+        let table_name = match Self::get_table_name(event_type) {
+            Some(table_name_) if event_index.is_kept_for(event_type) => table_name_,
+            _ => {
+                return Ok(0);
+            }
+        };
+
+        match self.count_indexed_event(table_name, event_index).await {
+            Ok(quantity) => {
+                return Ok(quantity);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the indexed event quantity".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_indexed_page(
+        &self,
+        event_type: EventType,
+        event_index: EventIndex,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Result<Vec<EventEntry>, Error> {
+        let table_name = match Self::get_table_name(event_type) {
+            Some(table_name_) if event_index.is_kept_for(event_type) => table_name_,
+            _ => {
+                return Ok(vec![]);
+            }
+        };
+
+        let (after_checkpoint, offset) = match page_start {
+            EventPageStart::Offset(offset_) => (None, offset_),
+            EventPageStart::After(cursor) => match EventCheckpoint::parse_cursor(cursor.as_str()) {
+                Some(checkpoint) => (Some(checkpoint), 0),
+                None => {
+                    return Err(Error::from_cursor(cursor.as_str()));
+                }
+            },
+        };
+
+        match self
+            .get_indexed_event_page(
+                event_type,
+                table_name,
+                event_index,
+                after_checkpoint,
+                offset,
+                limit,
+            )
+            .await
+        {
+            Ok(event_entry_registry) => {
+                return Ok(event_entry_registry);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the indexed events".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn get_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<Option<EventCheckpoint>, Error> {
+        let checkpoint = match self.connection_pool.begin().await {
+            Ok(mut transaction) => {
+                Self::get_checkpoint_in_transaction(&mut transaction, contract_address, event_type)
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+
+        match checkpoint {
+            Ok(checkpoint_) => {
+                return Ok(checkpoint_);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the checkpoint".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    async fn set_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        let result = match self.connection_pool.begin().await {
+            Ok(mut transaction) => {
+                match Self::set_checkpoint_in_transaction(
+                    &mut transaction,
+                    contract_address,
+                    event_type,
+                    checkpoint,
+                )
+                .await
+                {
+                    Ok(_) => transaction.commit().await,
+                    Err(error) => Err(error),
+                }
+            }
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            return Err(Error::Sql {
+                context: "Failed to set the checkpoint".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn remove_checkpoint(
+        &self,
+        contract_address: Address,
+        event_type: EventType,
+    ) -> Result<(), Error> {
+        if let Err(error) =
+            sqlx::query("DELETE FROM checkpoints WHERE contract_address = $1 AND event_type = $2")
+                .bind(format!("{:?}", contract_address))
+                .bind(event_type.get_name())
+                .execute(&self.connection_pool)
+                .await
+        {
+            return Err(Error::Sql {
+                context: "Failed to remove the checkpoint".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn index_ownership(&self, token_ownership: TokenOwnership) -> Result<(), Error> {
+        if let Err(error) = self.index_token_ownership(token_ownership).await {
+            return Err(Error::Sql {
+                context: "Failed to index the token ownership".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn retract_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+        checkpoint: EventCheckpoint,
+    ) -> Result<(), Error> {
+        if let Err(error) = self
+            .retract_token_ownership(collection, token_id, checkpoint)
+            .await
+        {
+            return Err(Error::Sql {
+                context: "Failed to retract the token ownership".to_string(),
+                source: error,
+            });
+        }
+
+        return Ok(());
+    }
+
+    async fn get_token_ownership(
+        &self,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<Option<TokenOwnership>, Error> {
+        let token_ownership = match self.connection_pool.begin().await {
+            Ok(mut transaction) => {
+                Self::get_token_ownership_in_transaction(&mut transaction, collection, token_id)
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+
+        match token_ownership {
+            Ok(token_ownership_) => {
+                return Ok(token_ownership_);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the token ownership".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
 
-    pub fn create_key_event<'a>(key_event_part: &'a str, event_index: i64) -> String {
-        return format!("{}{}{}", key_event_part, Self::SEPARATOR, event_index);
+    async fn get_owned_token_registry(&self, owner: Address) -> Result<Vec<OwnedToken>, Error> {
+        match self.get_owned_tokens(owner).await {
+            Ok(owned_token_registry) => {
+                return Ok(owned_token_registry);
+            }
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the owned tokens".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+
+    // Another process may store the events, so the changes are polled after the last sequence.
+    async fn subscribe(&self) -> Result<BoxStream<'static, EventNotification>, Error> {
+        let sequence = match Self::get_event_sequence(&self.connection_pool).await {
+            Ok(sequence_) => sequence_,
+            Err(error) => {
+                return Err(Error::Sql {
+                    context: "Failed to get the event sequence".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        return Ok(stream::unfold(
+            (self.connection_pool.clone(), sequence),
+            |(connection_pool, sequence_)| async move {
+                loop {
+                    match Self::get_event_notification_registry(&connection_pool, sequence_).await {
+                        Ok(event_notification_registry)
+                            if !event_notification_registry.is_empty() =>
+                        {
+                            let sequence__ = event_notification_registry
+                                .iter()
+                                .map(|(sequence___, _)| *sequence___)
+                                .max()
+                                .unwrap_or(sequence_);
+
+                            let event_notification_stream = stream::iter(
+                                event_notification_registry
+                                    .into_iter()
+                                    .map(|(_, event_notification)| event_notification),
+                            );
+
+                            return Some((
+                                event_notification_stream,
+                                (connection_pool, sequence__),
+                            ));
+                        }
+                        // The polling is repeated after a failure, like after an empty result.
+                        _ => {
+                            tokio::time::sleep(Self::EVENT_POLLING_INTERVAL).await;
+                        }
+                    }
+                }
+            },
+        )
+        .flatten()
+        .boxed());
+    }
+}
+
+enum SqlEntity {
+    Collection(CollectionCreatedEvent),
+    Mint(TokenMintedEvent),
+    Transfer(TransferEvent),
+    Nothing,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Redis {
+        context: String,
+        source: RedisError,
+    },
+    Pool {
+        context: String,
+        source: RunError<RedisError>,
+    },
+    Sql {
+        context: String,
+        source: SqlxError,
+    },
+    Transport {
+        context: String,
+        source: Web3Error,
+    },
+    Abi {
+        context: String,
+        source: Option<Box<dyn StdError + Send + Sync + 'static>>,
+    },
+    Decoding {
+        context: String,
+        source: Option<Box<dyn StdError + Send + Sync + 'static>>,
+    },
+    Config {
+        context: String,
+        source: Option<Box<dyn StdError + Send + Sync + 'static>>,
+    },
+    Serialization {
+        context: String,
+        source: SerdeJsonError,
+    },
+    NotFound {
+        context: String,
+    },
+    Transaction {
+        context: String,
+    },
+}
+
+impl Error {
+    pub fn from_cursor<'a>(cursor: &'a str) -> Self {
+        return Self::NotFound {
+            context: format!("The cursor '{}' is not found", cursor),
+        };
+    }
+
+    // The transport error is unwrapped, so that callers can recognize it as retryable.
+    pub fn from_contract_error<'a>(context: &'a str, error: Web3ContractError) -> Self {
+        match error {
+            Web3ContractError::Api(error_) => {
+                return Self::Transport {
+                    context: context.to_string(),
+                    source: error_,
+                };
+            }
+            _ => {
+                return Self::Decoding {
+                    context: context.to_string(),
+                    source: Some(Box::from(error)),
+                };
+            }
+        }
+    }
+
+    pub fn get_context(&self) -> &str {
+        match *self {
+            Self::Redis { ref context, .. }
+            | Self::Pool { ref context, .. }
+            | Self::Sql { ref context, .. }
+            | Self::Transport { ref context, .. }
+            | Self::Abi { ref context, .. }
+            | Self::Decoding { ref context, .. }
+            | Self::Config { ref context, .. }
+            | Self::Serialization { ref context, .. }
+            | Self::NotFound { ref context }
+            | Self::Transaction { ref context } => {
+                return context.as_str();
+            }
+        }
+    }
+
+    // The context is followed by the source, which is what is printed for the user.
+    pub fn get_report(&self) -> String {
+        match StdError::source(self) {
+            Some(source) => {
+                return format!("{}: {}", self.get_context(), source);
+            }
+            None => {
+                return self.to_string();
+            }
+        }
+    }
+
+    // The operation may succeed if it is repeated, after reconnecting if needed.
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Self::Redis { ref source, .. } => {
+                return source.is_io_error()
+                    || source.is_connection_dropped()
+                    || source.is_connection_refusal()
+                    || source.is_timeout();
+            }
+            Self::Sql { ref source, .. } => match *source {
+                SqlxError::Io(_) | SqlxError::PoolTimedOut => {
+                    return true;
+                }
+                _ => {
+                    return false;
+                }
+            },
+            Self::Pool { .. } | Self::Transport { .. } => {
+                return true;
+            }
+            Self::Abi { .. }
+            | Self::Decoding { .. }
+            | Self::Config { .. }
+            | Self::Serialization { .. }
+            | Self::NotFound { .. }
+            | Self::Transaction { .. } => {
+                return false;
+            }
+        }
+    }
+}
+
+// Only the context is displayed, because the source is reported by itself.
+impl Display for Error {
+    fn fmt<'a>(&'a self, formatter: &'a mut Formatter<'_>) -> std::fmt::Result {
+        return write!(formatter, "{}.", self.get_context());
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            Self::Redis { ref source, .. } => {
+                return Some(source);
+            }
+            Self::Pool { ref source, .. } => {
+                return Some(source);
+            }
+            Self::Sql { ref source, .. } => {
+                return Some(source);
+            }
+            Self::Transport { ref source, .. } => {
+                return Some(source);
+            }
+            Self::Serialization { ref source, .. } => {
+                return Some(source);
+            }
+            Self::Abi { ref source, .. }
+            | Self::Decoding { ref source, .. }
+            | Self::Config { ref source, .. } => match *source {
+                Some(ref source_) => {
+                    return Some(source_.as_ref());
+                }
+                None => {
+                    return None;
+                }
+            },
+            Self::NotFound { .. } | Self::Transaction { .. } => {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::future::Future;
+
+    // Every event is the only one of its block, with its block number as the token id and the transaction hash.
+    async fn append_token_minted<'a, S>(
+        event_store: &'a S,
+        collection: Address,
+        block_number: u64,
+        status: EventStatus,
+    ) -> bool
+    where
+        S: EventStore,
+    {
+        let token_minted_event = TokenMintedEvent {
+            collection,
+            recipient: Address::repeat_byte(2),
+            token_id: U256::from(block_number),
+            token_uri: format!("ipfs://{}", block_number),
+            block_number,
+            tx_hash: H256::from_low_u64_be(block_number),
+            log_index: 0,
+        };
+
+        return event_store
+            .append(
+                collection,
+                EventType::TokenMinted,
+                EventCheckpoint {
+                    block_number,
+                    log_index: 0,
+                },
+                token_minted_event.tx_hash,
+                status,
+                serde_json::to_string(&EventRecord {
+                    status,
+                    event: token_minted_event,
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn get_cursor_registry<'a, S>(
+        event_store: &'a S,
+        page_start: EventPageStart,
+        limit: i64,
+    ) -> Vec<String>
+    where
+        S: EventStore,
+    {
+        return event_store
+            .get_page(EventType::TokenMinted, page_start, limit)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|event_entry| event_entry.cursor)
+            .collect::<Vec<String>>();
+    }
+
+    async fn create_sql_event_store() -> SqlEventStore {
+        // Every connection to the in-memory database has its own one.
+        return SqlEventStore::new(
+            SqlConnectionPoolCreator::create("sqlite::memory:", 1)
+                .await
+                .unwrap(),
+        );
+    }
+
+    // The cursors differ between the stores, so they are read from the pages.
+    async fn check_append_skips_stored_event<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let collection = Address::repeat_byte(1);
+
+        assert!(append_token_minted(event_store, collection, 1, EventStatus::Final).await);
+        assert!(!append_token_minted(event_store, collection, 1, EventStatus::Final).await);
+        assert!(append_token_minted(event_store, collection, 2, EventStatus::Final).await);
+
+        assert_eq!(event_store.count(EventType::TokenMinted).await.unwrap(), 2);
+
+        let cursor_registry = get_cursor_registry(event_store, EventPageStart::Offset(0), 5).await;
+
+        assert_eq!(cursor_registry.len(), 2);
+        assert_eq!(
+            event_store
+                .get_last_cursor(EventType::TokenMinted)
+                .await
+                .unwrap(),
+            cursor_registry[1]
+        );
+
+        return ();
+    }
+
+    async fn check_retract_keeps_cursors<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let collection = Address::repeat_byte(1);
+
+        for block_number in 1..=3 {
+            append_token_minted(event_store, collection, block_number, EventStatus::Final).await;
+        }
+
+        let cursor_registry = get_cursor_registry(event_store, EventPageStart::Offset(0), 5).await;
+
+        event_store
+            .retract(
+                EventType::TokenMinted,
+                EventCheckpoint {
+                    block_number: 2,
+                    log_index: 0,
+                },
+                H256::from_low_u64_be(2),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(event_store.count(EventType::TokenMinted).await.unwrap(), 2);
+        assert!(event_store
+            .get_by_cursor(EventType::TokenMinted, cursor_registry[1].as_str())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(event_store
+            .get_by_cursor(EventType::TokenMinted, cursor_registry[2].as_str())
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            get_cursor_registry(event_store, EventPageStart::Offset(0), 5).await,
+            vec![cursor_registry[0].clone(), cursor_registry[2].clone()]
+        );
+        assert_eq!(
+            get_cursor_registry(event_store, EventPageStart::Offset(1), 1).await,
+            vec![cursor_registry[2].clone()]
+        );
+        assert_eq!(
+            event_store
+                .count_indexed(EventType::TokenMinted, EventIndex::Collection(collection))
+                .await
+                .unwrap(),
+            2
+        );
+
+        // The retracted log is stored again under a new cursor if a later block includes it.
+        assert!(append_token_minted(event_store, collection, 2, EventStatus::Final).await);
+
+        let last_cursor = event_store
+            .get_last_cursor(EventType::TokenMinted)
+            .await
+            .unwrap();
+
+        assert!(!cursor_registry.contains(&last_cursor));
+        assert!(event_store
+            .get_by_cursor(EventType::TokenMinted, last_cursor.as_str())
+            .await
+            .unwrap()
+            .is_some());
+
+        return ();
+    }
+
+    async fn check_confirm_finalizes_pending_events<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let collection = Address::repeat_byte(1);
+
+        for block_number in 1..=3 {
+            append_token_minted(event_store, collection, block_number, EventStatus::Pending).await;
+        }
+
+        event_store
+            .confirm(EventType::TokenMinted, 2)
+            .await
+            .unwrap();
+
+        // The streams have only the final events, and the other stores have the pending ones too.
+        let mut final_block_number_registry: Vec<u64> = vec![];
+
+        for event_entry in event_store
+            .get_page(EventType::TokenMinted, EventPageStart::Offset(0), 5)
+            .await
+            .unwrap()
+            .into_iter()
+        {
+            let event_record =
+                serde_json::from_str::<EventRecord<TokenMintedEvent>>(event_entry.data.as_str())
+                    .unwrap();
+
+            if event_record.status == EventStatus::Final {
+                final_block_number_registry.push(event_record.event.block_number);
+            } else {
+                assert!(event_record.status == EventStatus::Pending);
+                assert_eq!(event_record.event.block_number, 3);
+            }
+        }
+
+        assert_eq!(final_block_number_registry, vec![1, 2]);
+
+        return ();
+    }
+
+    async fn check_page_starts_at_offset_or_after_cursor<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let collection = Address::repeat_byte(1);
+
+        for block_number in 1..=5 {
+            append_token_minted(event_store, collection, block_number, EventStatus::Final).await;
+        }
+
+        let cursor_registry = get_cursor_registry(event_store, EventPageStart::Offset(0), 5).await;
+
+        assert_eq!(cursor_registry.len(), 5);
+        assert_eq!(
+            get_cursor_registry(event_store, EventPageStart::Offset(1), 2).await,
+            cursor_registry[1..3].to_vec()
+        );
+        assert_eq!(
+            get_cursor_registry(
+                event_store,
+                EventPageStart::After(cursor_registry[2].clone()),
+                5
+            )
+            .await,
+            cursor_registry[3..].to_vec()
+        );
+        assert!(matches!(
+            event_store
+                .get_page(
+                    EventType::TokenMinted,
+                    EventPageStart::After("x".to_string()),
+                    1
+                )
+                .await,
+            Err(Error::NotFound { .. })
+        ));
+
+        let event_entry_registry = event_store
+            .get_indexed_page(
+                EventType::TokenMinted,
+                EventIndex::Collection(collection),
+                EventPageStart::After("2:0".to_string()),
+                2,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            event_entry_registry
+                .into_iter()
+                .map(|event_entry| event_entry.cursor)
+                .collect::<Vec<String>>(),
+            vec!["3:0", "4:0"]
+        );
+
+        return ();
+    }
+
+    async fn check_checkpoint_never_moves_back<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let collection = Address::repeat_byte(1);
+
+        assert!(event_store
+            .get_checkpoint(collection, EventType::TokenMinted)
+            .await
+            .unwrap()
+            .is_none());
+
+        append_token_minted(event_store, collection, 5, EventStatus::Final).await;
+        append_token_minted(event_store, collection, 3, EventStatus::Final).await;
+
+        assert!(
+            event_store
+                .get_checkpoint(collection, EventType::TokenMinted)
+                .await
+                .unwrap()
+                == Some(EventCheckpoint {
+                    block_number: 5,
+                    log_index: 0,
+                })
+        );
+
+        event_store
+            .remove_checkpoint(collection, EventType::TokenMinted)
+            .await
+            .unwrap();
+
+        assert!(event_store
+            .get_checkpoint(collection, EventType::TokenMinted)
+            .await
+            .unwrap()
+            .is_none());
+
+        return ();
+    }
+
+    async fn check_subscription_yields_stored_events<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let mut event_notification_stream = event_store.subscribe().await.unwrap();
+
+        append_token_minted(event_store, Address::repeat_byte(1), 1, EventStatus::Final).await;
+
+        let event_notification = event_notification_stream.next().await.unwrap();
+
+        assert_eq!(event_notification.event_type, "token_minted");
+        assert!(event_notification.change == EventChange::Appended);
+        assert_eq!(
+            event_notification.cursor,
+            event_store
+                .get_last_cursor(EventType::TokenMinted)
+                .await
+                .unwrap()
+        );
+        assert_eq!(event_notification.record["event"]["block_number"], 1);
+
+        return ();
+    }
+
+    async fn index_ownership<'a, S>(
+        event_store: &'a S,
+        owner: Address,
+        block_number: u64,
+        log_index: u64,
+    ) -> ()
+    where
+        S: EventStore,
+    {
+        return event_store
+            .index_ownership(TokenOwnership {
+                collection: Address::repeat_byte(1),
+                token_id: U256::from(7),
+                owner,
+                block_number,
+                log_index,
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn retract_ownership<'a, S>(event_store: &'a S, block_number: u64, log_index: u64) -> ()
+    where
+        S: EventStore,
+    {
+        return event_store
+            .retract_ownership(
+                Address::repeat_byte(1),
+                U256::from(7),
+                EventCheckpoint {
+                    block_number,
+                    log_index,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn get_owner<'a, S>(event_store: &'a S) -> Option<Address>
+    where
+        S: EventStore,
+    {
+        return event_store
+            .get_token_ownership(Address::repeat_byte(1), U256::from(7))
+            .await
+            .unwrap()
+            .map(|token_ownership| token_ownership.owner);
+    }
+
+    async fn check_ownership_follows_latest_transfer<'a, S>(event_store: &'a S) -> ()
+    where
+        S: EventStore,
+    {
+        let (minter, owner) = (Address::repeat_byte(4), Address::repeat_byte(5));
+
+        index_ownership(event_store, minter, 5, 0).await;
+        index_ownership(event_store, Address::zero(), 12, 0).await;
+        // An earlier transfer does not change the ownership, and indexing it again is harmless.
+        index_ownership(event_store, owner, 10, 1).await;
+        index_ownership(event_store, owner, 10, 1).await;
+
+        assert_eq!(get_owner(event_store).await, Some(Address::zero()));
+        assert!(event_store
+            .get_owned_token_registry(owner)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The burn is retracted, so the token is back with the owner of the previous transfer.
+        retract_ownership(event_store, 12, 0).await;
+
+        assert_eq!(get_owner(event_store).await, Some(owner));
+        assert_eq!(
+            event_store
+                .get_owned_token_registry(owner)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        retract_ownership(event_store, 10, 1).await;
+        // A transfer that is not indexed is not retracted.
+        retract_ownership(event_store, 10, 2).await;
+
+        assert_eq!(get_owner(event_store).await, Some(minter));
+        assert!(event_store
+            .get_owned_token_registry(owner)
+            .await
+            .unwrap()
+            .is_empty());
+
+        retract_ownership(event_store, 5, 0).await;
+
+        assert_eq!(get_owner(event_store).await, None);
+        assert!(event_store
+            .get_owned_token_registry(minter)
+            .await
+            .unwrap()
+            .is_empty());
+
+        return ();
+    }
+
+    // Every check is run against an empty store.
+    async fn check_event_store<S, F, R>(create_event_store: F) -> ()
+    where
+        S: EventStore,
+        F: Fn() -> R,
+        R: Future<Output = S>,
+    {
+        check_append_skips_stored_event(&create_event_store().await).await;
+        check_retract_keeps_cursors(&create_event_store().await).await;
+        check_confirm_finalizes_pending_events(&create_event_store().await).await;
+        check_page_starts_at_offset_or_after_cursor(&create_event_store().await).await;
+        check_checkpoint_never_moves_back(&create_event_store().await).await;
+        check_subscription_yields_stored_events(&create_event_store().await).await;
+        check_ownership_follows_latest_transfer(&create_event_store().await).await;
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn in_memory_event_store_passes_checks() -> () {
+        check_event_store(|| async { InMemoryEventStore::new() }).await;
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn sql_event_store_passes_checks() -> () {
+        check_event_store(create_sql_event_store).await;
+
+        return ();
+    }
+
+    #[tokio::test]
+    async fn in_memory_subscription_ends_after_missed_events() -> () {
+        let event_store = InMemoryEventStore::new();
+
+        let mut event_notification_stream = event_store.subscribe().await.unwrap();
+
+        for block_number in 1..=(InMemoryEventStore::EVENT_NOTIFICATION_CAPACITY as u64 + 1) {
+            append_token_minted(
+                &event_store,
+                Address::repeat_byte(1),
+                block_number,
+                EventStatus::Final,
+            )
+            .await;
+        }
+
+        assert!(event_notification_stream.next().await.is_none());
+
+        return ();
+    }
+
+    // The connections of the pool share a database file, so the appends run in concurrent transactions.
+    #[tokio::test]
+    async fn sql_event_store_allocates_concurrent_positions() -> () {
+        let database_path =
+            env::temp_dir().join(format!("erc721_events_{}.db", std::process::id()));
+
+        let _ = fs::remove_file(database_path.as_path());
+
+        let event_store = SqlEventStore::new(
+            SqlConnectionPoolCreator::create(
+                format!("sqlite://{}?mode=rwc", database_path.display()).as_str(),
+                4,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let collection = Address::repeat_byte(1);
+
+        let append_registry = web3::futures::future::join_all((1..=8).map(|block_number| {
+            append_token_minted(&event_store, collection, block_number, EventStatus::Final)
+        }))
+        .await;
+
+        assert!(append_registry.into_iter().all(|is_appended| is_appended));
+
+        let mut position_registry =
+            get_cursor_registry(&event_store, EventPageStart::Offset(0), 10)
+                .await
+                .into_iter()
+                .map(|cursor| cursor.parse::<i64>().unwrap())
+                .collect::<Vec<i64>>();
+
+        position_registry.sort_unstable();
+
+        assert_eq!(position_registry, (1..=8).collect::<Vec<i64>>());
+
+        let _ = fs::remove_file(database_path.as_path());
+
+        return ();
+    }
+
+    // Runs the scripts against the Redis of the 'ERC721_TEST_REDIS_URL', whose database is flushed, like
+    // 'ERC721_TEST_REDIS_URL=redis://127.0.0.1/15 cargo test -- --ignored'.
+    #[tokio::test]
+    #[ignore]
+    async fn redis_event_store_passes_checks() -> () {
+        let redis_url = env::var("ERC721_TEST_REDIS_URL").unwrap();
+
+        for storage_mode in [StorageMode::Keys, StorageMode::Streams] {
+            check_event_store(|| async {
+                let connection_pool = ConnectionPoolCreator::create(redis_url.as_str(), 1)
+                    .await
+                    .unwrap();
+
+                redis::cmd("FLUSHDB")
+                    .query_async::<_, ()>(&mut *connection_pool.get().await.unwrap())
+                    .await
+                    .unwrap();
+
+                RedisEventStore::new(connection_pool, redis_url.clone(), storage_mode)
+            })
+            .await;
+        }
+
+        // The events of the numbered keys are copied to the streams, where the pending ones wait until
+        // they are final.
+        let connection_pool = ConnectionPoolCreator::create(redis_url.as_str(), 1)
+            .await
+            .unwrap();
+
+        redis::cmd("FLUSHDB")
+            .query_async::<_, ()>(&mut *connection_pool.get().await.unwrap())
+            .await
+            .unwrap();
+
+        let event_store = RedisEventStore::new(
+            connection_pool.clone(),
+            redis_url.clone(),
+            StorageMode::Keys,
+        );
+
+        let collection = Address::repeat_byte(1);
+
+        append_token_minted(&event_store, collection, 3, EventStatus::Final).await;
+        append_token_minted(&event_store, collection, 1, EventStatus::Final).await;
+        append_token_minted(&event_store, collection, 2, EventStatus::Pending).await;
+        append_token_minted(&event_store, collection, 4, EventStatus::Final).await;
+
+        event_store
+            .retract(
+                EventType::TokenMinted,
+                EventCheckpoint {
+                    block_number: 4,
+                    log_index: 0,
+                },
+                H256::from_low_u64_be(4),
+            )
+            .await
+            .unwrap();
+
+        for migrated_quantity in [3, 0] {
+            let event_migration = event_store
+                .migrate_to_streams()
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|event_migration| event_migration.event_type == EventType::TokenMinted)
+                .unwrap();
+
+            assert_eq!(event_migration.quantity, 3);
+            assert_eq!(event_migration.migrated_quantity, migrated_quantity);
+        }
+
+        let stream_event_store =
+            RedisEventStore::new(connection_pool, redis_url.clone(), StorageMode::Streams);
+
+        assert_eq!(
+            get_cursor_registry(&stream_event_store, EventPageStart::Offset(0), 5).await,
+            vec!["1-0", "3-0"]
+        );
+
+        stream_event_store
+            .confirm(EventType::TokenMinted, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stream_event_store
+                .count(EventType::TokenMinted)
+                .await
+                .unwrap(),
+            3
+        );
+
+        return ();
+    }
+
+    #[test]
+    fn decoder_decodes_token_minted_log() -> () {
+        let event = ContractAbiLoader::load_collection_aggregator()
+            .unwrap()
+            .event("TokenMinted")
+            .unwrap();
+
+        let collection = Address::repeat_byte(1);
+
+        let recipient = Address::repeat_byte(2);
+
+        let log = Log {
+            address: Address::repeat_byte(9),
+            topics: vec![event.signature(), H256::from(collection)],
+            data: Bytes(web3::ethabi::encode(&[
+                Token::Address(recipient),
+                Token::Uint(U256::from(7)),
+                Token::String("ipfs://7".to_string()),
+            ])),
+            block_hash: None,
+            block_number: Some(U64::from(12)),
+            transaction_hash: Some(H256::repeat_byte(3)),
+            transaction_index: None,
+            log_index: Some(U256::from(4)),
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+
+        let token_minted_event = ContractEventDecoder::decode_token_minted(&log).unwrap();
+
+        assert_eq!(token_minted_event.collection, collection);
+        assert_eq!(token_minted_event.recipient, recipient);
+        assert_eq!(token_minted_event.token_id, U256::from(7));
+        assert_eq!(token_minted_event.token_uri, "ipfs://7");
+        assert_eq!(token_minted_event.block_number, 12);
+        assert_eq!(token_minted_event.tx_hash, H256::repeat_byte(3));
+        assert_eq!(token_minted_event.log_index, 4);
+
+        // A log of another event is not decoded as this one.
+        assert!(ContractEventDecoder::decode_collection_created(&log).is_err());
+
+        return ();
+    }
+
+    #[test]
+    fn checkpoint_previous_crosses_block() -> () {
+        let checkpoint = EventCheckpoint {
+            block_number: 5,
+            log_index: 2,
+        };
+
+        assert!(
+            checkpoint.get_previous()
+                == Some(EventCheckpoint {
+                    block_number: 5,
+                    log_index: 1,
+                })
+        );
+
+        let first_checkpoint = EventCheckpoint {
+            block_number: 5,
+            log_index: 0,
+        };
+
+        assert!(
+            first_checkpoint.get_previous()
+                == Some(EventCheckpoint {
+                    block_number: 4,
+                    log_index: u64::MAX,
+                })
+        );
+        assert!(first_checkpoint.get_previous().unwrap() < first_checkpoint);
+
+        let genesis_checkpoint = EventCheckpoint {
+            block_number: 0,
+            log_index: 0,
+        };
+
+        assert!(genesis_checkpoint.get_previous().is_none());
+
+        return ();
+    }
+
+    #[test]
+    fn configuration_validation_rejects_invalid_values() -> () {
+        assert!(ConfigurationLoader::validate(&Configuration::default()).is_ok());
+
+        let mut configuration = Configuration::default();
+        configuration.ethereum.websocket_url = "http://ganache:8545".to_string();
+        assert!(ConfigurationLoader::validate(&configuration).is_err());
+
+        let mut configuration = Configuration::default();
+        configuration.server.socket_address = "0.0.0.0".to_string();
+        assert!(ConfigurationLoader::validate(&configuration).is_err());
+
+        let mut configuration = Configuration::default();
+        configuration.contract.address = "0x12".to_string();
+        assert!(ConfigurationLoader::validate(&configuration).is_err());
+
+        // The SQL URL is checked only for the SQL backend.
+        let mut configuration = Configuration::default();
+        configuration.storage.sql_url = "mysql://database".to_string();
+        assert!(ConfigurationLoader::validate(&configuration).is_ok());
+        configuration.storage.backend = StorageBackend::Sql;
+        assert!(ConfigurationLoader::validate(&configuration).is_err());
+
+        return ();
     }
 }
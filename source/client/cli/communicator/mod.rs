@@ -70,6 +70,10 @@
     clippy::zero_sized_map_values
 )]
 
+use bip32::DerivationPath;
+use bip32::XPrv;
+use bip39::Language;
+use bip39::Mnemonic;
 use clap::crate_description;
 use clap::crate_name;
 use clap::crate_version;
@@ -77,18 +81,48 @@ use clap::value_t_or_exit;
 use clap::App;
 use clap::AppSettings;
 use clap::Arg;
+use clap::ArgGroup;
+use clap::ArgMatches;
 use clap::SubCommand;
-use serde_json::Value;
-use std::error::Error;
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fs::File;
+use infrastructure::serde_json;
+use infrastructure::serde_json::json;
+use infrastructure::serde_json::Map;
+use infrastructure::serde_json::Value;
+use infrastructure::CollectionMetadataReader;
+use infrastructure::CollectionRegistryReader;
+use infrastructure::ConfigurationLoader;
+use infrastructure::ContractAbiLoader;
+use infrastructure::ContractBytecodeLoader;
+use infrastructure::ContractEventDecoder;
+use infrastructure::Error;
+use infrastructure::EventTopicCreator;
+use secp256k1::SecretKey;
+use std::cmp;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
 use std::str::FromStr;
-use web3::contract::Contract;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time;
+use web3::contract::tokens::Detokenize;
+use web3::contract::tokens::Tokenize;
+use web3::contract::Contract as Web3Contract;
 use web3::contract::Options;
+use web3::ethabi::Contract;
+use web3::signing::Key;
 use web3::transports::Http;
 use web3::types::Address;
+use web3::types::BlockNumber;
+use web3::types::Bytes;
+use web3::types::CallRequest;
+use web3::types::Log;
+use web3::types::TransactionParameters;
+use web3::types::TransactionReceipt;
+use web3::types::TransactionRequest;
+use web3::types::H256;
+use web3::types::U256;
+use web3::types::U64;
 use web3::Web3;
 
 const COMMAND_CREATE_COLLECTION_ARGUMENT_NAME: &'static str = "name";
@@ -98,291 +132,2351 @@ const COMMAND_MINT_ARGUMENT_COLLECTION: &'static str = "collection";
 const COMMAND_MINT_ARGUMENT_RECIPIENT: &'static str = "recipient";
 const COMMAND_MINT_ARGUMENT_TOKEN_URI: &'static str = "tokenUri";
 const COMMAND_MINT: &'static str = "mint";
-const CONTRACT_METADATA_FILE_PATH: &'static str =
-    "/_task/source/contract/build/contracts/CollectionAggregator.json";
+const COMMAND_DEPLOY_ARGUMENT_ARTIFACT: &'static str = "artifact";
+const COMMAND_DEPLOY: &'static str = "deploy";
+const COMMAND_TRANSFER_ARGUMENT_RECIPIENT: &'static str = "recipient";
+const COMMAND_TRANSFER: &'static str = "transfer";
+const COMMAND_APPROVE_ARGUMENT_APPROVED: &'static str = "approved";
+const COMMAND_APPROVE: &'static str = "approve";
+const COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR: &'static str = "operator";
+const COMMAND_APPROVE_ALL: &'static str = "approve-all";
+const COMMAND_REVOKE_ARGUMENT_REVOKED: &'static str = "revoked";
+const COMMAND_REVOKE: &'static str = "revoke";
+const COMMAND_COLLECTIONS: &'static str = "collections";
+const COMMAND_COLLECTIONS_LIST_ARGUMENT_OFFSET: &'static str = "offset";
+const COMMAND_COLLECTIONS_LIST_ARGUMENT_LIMIT: &'static str = "limit";
+const COMMAND_COLLECTIONS_LIST: &'static str = "list";
+const COMMAND_COLLECTIONS_EXISTS: &'static str = "exists";
+const COMMAND_NAME: &'static str = "name";
+const COMMAND_SYMBOL: &'static str = "symbol";
+const COMMAND_OWNER_OF: &'static str = "owner-of";
+const COMMAND_TOKEN_URI: &'static str = "token-uri";
+const COMMAND_BALANCE_OF: &'static str = "balance-of";
+const COMMAND_GET_METHOD_CALLER: &'static str = "get-method-caller";
+const COMMAND_QUERY_ARGUMENT_COLLECTION: &'static str = "collection";
+const COMMAND_QUERY_ARGUMENT_TOKEN_ID: &'static str = "tokenId";
+const COMMAND_QUERY_ARGUMENT_OWNER: &'static str = "owner";
 const FROM_ADRESS: &'static str = "from";
-const GANACHE_URL: &'static str = "http://ganache:8545";
 const TO_ADDRESS: &'static str = "to";
+const JSON: &'static str = "json";
+const RECEIPT_CONFIRMATIONS: &'static str = "confirmations";
+const RECEIPT_POLL_INTERVAL_MILLISECONDS: u64 = 1000;
+const RECEIPT_TIMEOUT: &'static str = "timeout";
+const SIGNER: &'static str = "signer";
+const SIGNER_PRIVATE_KEY: &'static str = "private-key";
+const SIGNER_KEYSTORE: &'static str = "keystore";
+const SIGNER_MNEMONIC: &'static str = "mnemonic";
+const SIGNER_DERIVATION_PATH: &'static str = "derivation-path";
+const SIGNER_DEFAULT_DERIVATION_PATH: &'static str = "m/44'/60'/0'/0/0";
 
-fn main() -> () {
+fn main() -> ExitCode {
     let matches = App::new(crate_name!())
         .about(crate_description!())
         .version(crate_version!())
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .subcommand(
-            SubCommand::with_name(COMMAND_CREATE_COLLECTION)
-                .about("Create new NFT collection.")
-                .arg(
-                    Arg::with_name(FROM_ADRESS)
-                        .index(1)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Sender address."),
-                )
-                .arg(
-                    Arg::with_name(TO_ADDRESS)
-                        .index(2)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Called contract address."),
-                )
-                .arg(
-                    Arg::with_name(COMMAND_CREATE_COLLECTION_ARGUMENT_NAME)
-                        .index(3)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Collection name."),
-                )
-                .arg(
-                    Arg::with_name(COMMAND_CREATE_COLLECTION_ARGUMENT_SYMBOL)
-                        .index(4)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Collection symbol"),
-                ),
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_DEPLOY)
+                    .about("Deploy the CollectionAggregator contract."),
+            )
+            .arg(
+                Arg::with_name(COMMAND_DEPLOY_ARGUMENT_ARTIFACT)
+                    .long(COMMAND_DEPLOY_ARGUMENT_ARTIFACT)
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("Contract artifact, the configured contract metadata file by default."),
+            ),
         )
         .subcommand(
-            SubCommand::with_name(COMMAND_MINT)
-                .about("Mint NFT into collection.")
-                .arg(
-                    Arg::with_name(FROM_ADRESS)
-                        .index(1)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Sender address."),
-                )
-                .arg(
-                    Arg::with_name(TO_ADDRESS)
-                        .index(2)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Called contract address."),
-                )
-                .arg(
-                    Arg::with_name(COMMAND_MINT_ARGUMENT_COLLECTION)
-                        .index(3)
-                        .takes_value(true)
-                        .required(true)
-                        .help("ERC721 contract address."),
-                )
-                .arg(
-                    Arg::with_name(COMMAND_MINT_ARGUMENT_RECIPIENT)
-                        .index(4)
-                        .takes_value(true)
-                        .required(true)
-                        .help("NFT recipient address."),
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_CREATE_COLLECTION)
+                    .about("Create new NFT collection."),
+            )
+            .arg(create_to_argument())
+            .arg(
+                Arg::with_name(COMMAND_CREATE_COLLECTION_ARGUMENT_NAME)
+                    .index(1)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Collection name."),
+            )
+            .arg(
+                Arg::with_name(COMMAND_CREATE_COLLECTION_ARGUMENT_SYMBOL)
+                    .index(2)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Collection symbol"),
+            ),
+        )
+        .subcommand(
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_MINT).about("Mint NFT into collection."),
+            )
+            .arg(create_to_argument())
+            .arg(
+                Arg::with_name(COMMAND_MINT_ARGUMENT_COLLECTION)
+                    .index(1)
+                    .takes_value(true)
+                    .required(true)
+                    .help("ERC721 contract address."),
+            )
+            .arg(
+                Arg::with_name(COMMAND_MINT_ARGUMENT_RECIPIENT)
+                    .index(2)
+                    .takes_value(true)
+                    .required(true)
+                    .help("NFT recipient address."),
+            )
+            .arg(
+                Arg::with_name(COMMAND_MINT_ARGUMENT_TOKEN_URI)
+                    .index(3)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Token Uri."),
+            ),
+        )
+        .subcommand(
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_TRANSFER)
+                    .about("Transfer the token of the collection."),
+            )
+            .arg(create_collection_argument())
+            .arg(create_token_id_argument())
+            .arg(
+                Arg::with_name(COMMAND_TRANSFER_ARGUMENT_RECIPIENT)
+                    .index(3)
+                    .takes_value(true)
+                    .required(true)
+                    .help("NFT recipient address."),
+            ),
+        )
+        .subcommand(
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_APPROVE)
+                    .about("Approve the address to transfer the token."),
+            )
+            .arg(create_collection_argument())
+            .arg(create_token_id_argument())
+            .arg(
+                Arg::with_name(COMMAND_APPROVE_ARGUMENT_APPROVED)
+                    .index(3)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Approved address."),
+            ),
+        )
+        .subcommand(
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_APPROVE_ALL)
+                    .about("Approve the operator of all the tokens of the sender."),
+            )
+            .arg(create_collection_argument())
+            .arg(
+                Arg::with_name(COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR)
+                    .index(2)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Operator address."),
+            ),
+        )
+        .subcommand(
+            add_transaction_arguments(
+                SubCommand::with_name(COMMAND_REVOKE)
+                    .about("Revoke the approval of the token or the operator."),
+            )
+            .arg(create_collection_argument())
+            .arg(create_token_id_argument().required(false))
+            .arg(
+                Arg::with_name(COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR)
+                    .long(COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR)
+                    .takes_value(true)
+                    .value_name("ADDRESS")
+                    .help("Operator address."),
+            )
+            .group(
+                ArgGroup::with_name(COMMAND_REVOKE_ARGUMENT_REVOKED)
+                    .args(&[
+                        COMMAND_QUERY_ARGUMENT_TOKEN_ID,
+                        COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR,
+                    ])
+                    .required(true),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name(COMMAND_COLLECTIONS)
+                .about("Read the collection registry.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    add_output_arguments(
+                        SubCommand::with_name(COMMAND_COLLECTIONS_LIST)
+                            .about("List the registered collections."),
+                    )
+                    .arg(create_to_argument())
+                    .arg(
+                        Arg::with_name(COMMAND_COLLECTIONS_LIST_ARGUMENT_OFFSET)
+                            .long(COMMAND_COLLECTIONS_LIST_ARGUMENT_OFFSET)
+                            .takes_value(true)
+                            .value_name("INDEX")
+                            .default_value("0")
+                            .validator(validate_quantity)
+                            .help("Zero-based index of the first collection."),
+                    )
+                    .arg(
+                        Arg::with_name(COMMAND_COLLECTIONS_LIST_ARGUMENT_LIMIT)
+                            .long(COMMAND_COLLECTIONS_LIST_ARGUMENT_LIMIT)
+                            .takes_value(true)
+                            .value_name("QUANTITY")
+                            .validator(validate_quantity)
+                            .help("Maximum quantity of the collections, all of them by default."),
+                    ),
                 )
-                .arg(
-                    Arg::with_name(COMMAND_MINT_ARGUMENT_TOKEN_URI)
-                        .index(5)
-                        .takes_value(true)
-                        .required(true)
-                        .help("Token Uri."),
+                .subcommand(
+                    add_output_arguments(
+                        SubCommand::with_name(COMMAND_COLLECTIONS_EXISTS)
+                            .about("Check that the collection is registered."),
+                    )
+                    .arg(create_to_argument())
+                    .arg(create_collection_argument()),
                 ),
         )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_NAME).about("Read the name of the collection."),
+            )
+            .arg(create_collection_argument()),
+        )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_SYMBOL).about("Read the symbol of the collection."),
+            )
+            .arg(create_collection_argument()),
+        )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_OWNER_OF).about("Read the owner of the token."),
+            )
+            .arg(create_collection_argument())
+            .arg(create_token_id_argument()),
+        )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_TOKEN_URI).about("Read the URI of the token."),
+            )
+            .arg(create_collection_argument())
+            .arg(create_token_id_argument()),
+        )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_BALANCE_OF)
+                    .about("Read the quantity of the tokens of the owner."),
+            )
+            .arg(create_collection_argument())
+            .arg(
+                Arg::with_name(COMMAND_QUERY_ARGUMENT_OWNER)
+                    .index(2)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Owner address."),
+            ),
+        )
+        .subcommand(
+            add_output_arguments(
+                SubCommand::with_name(COMMAND_GET_METHOD_CALLER)
+                    .about("Read the caller that the collection sees."),
+            )
+            .arg(create_collection_argument())
+            .arg(
+                Arg::with_name(FROM_ADRESS)
+                    .long(FROM_ADRESS)
+                    .takes_value(true)
+                    .value_name("ADDRESS")
+                    .help("Caller address."),
+            ),
+        )
         .get_matches();
 
-    let transport = match Http::new(GANACHE_URL) {
-        Ok(transport_) => transport_,
+    let is_json = is_json_requested(&matches);
+
+    // The error goes to the standard error, so that the output of a failed command is empty.
+    match run(&matches) {
+        Ok(report) => {
+            report.print(is_json);
+
+            return ExitCode::SUCCESS;
+        }
         Err(error) => {
-            println!("{}", &error);
+            if is_json {
+                eprintln!("{}", json!({ "error": error.get_report() }));
+            } else {
+                eprintln!("{}", error.get_report());
+            }
+
+            return ExitCode::FAILURE;
+        }
+    }
+}
 
-            return ();
+fn run<'a>(matches: &'a ArgMatches<'a>) -> Result<Report, Error> {
+    let configuration = ConfigurationLoader::load()?;
+
+    let transport = match Http::new(configuration.ethereum.http_url.as_str()) {
+        Ok(transport_) => transport_,
+        Err(error) => {
+            return Err(Error::Transport {
+                context: format!(
+                    "Failed to create the transport of '{}'",
+                    configuration.ethereum.http_url
+                ),
+                source: error,
+            });
         }
     };
 
     let web3 = Web3::new(transport);
 
-    let result = match matches.subcommand() {
+    let deployments_file_path = configuration.contract.deployments_file_path.as_str();
+
+    match matches.subcommand() {
+        (COMMAND_DEPLOY, Some(arg_matches)) => {
+            return deploy(
+                &web3,
+                arg_matches
+                    .value_of(COMMAND_DEPLOY_ARGUMENT_ARTIFACT)
+                    .unwrap_or(configuration.contract.metadata_file_path.as_str()),
+                deployments_file_path,
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+            );
+        }
         (COMMAND_CREATE_COLLECTION, Some(arg_matches)) => {
-            let from = value_t_or_exit!(arg_matches, FROM_ADRESS, String);
+            return create_collection(
+                &web3,
+                configuration.contract.metadata_file_path.as_str(),
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                resolve_to_address(&web3, arg_matches, deployments_file_path)?,
+                value_t_or_exit!(arg_matches, COMMAND_CREATE_COLLECTION_ARGUMENT_NAME, String),
+                value_t_or_exit!(
+                    arg_matches,
+                    COMMAND_CREATE_COLLECTION_ARGUMENT_SYMBOL,
+                    String
+                ),
+            );
+        }
+        (COMMAND_MINT, Some(arg_matches)) => {
+            return mint(
+                &web3,
+                configuration.contract.metadata_file_path.as_str(),
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                resolve_to_address(&web3, arg_matches, deployments_file_path)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_MINT_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_MINT_ARGUMENT_RECIPIENT)?,
+                value_t_or_exit!(arg_matches, COMMAND_MINT_ARGUMENT_TOKEN_URI, String),
+            );
+        }
+        (COMMAND_TRANSFER, Some(arg_matches)) => {
+            return transfer(
+                &web3,
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_token_id(arg_matches, COMMAND_QUERY_ARGUMENT_TOKEN_ID)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_TRANSFER_ARGUMENT_RECIPIENT)?,
+            );
+        }
+        (COMMAND_APPROVE, Some(arg_matches)) => {
+            return approve(
+                &web3,
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_token_id(arg_matches, COMMAND_QUERY_ARGUMENT_TOKEN_ID)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_APPROVE_ARGUMENT_APPROVED)?,
+            );
+        }
+        (COMMAND_APPROVE_ALL, Some(arg_matches)) => {
+            return approve_all(
+                &web3,
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR)?,
+                true,
+            );
+        }
+        // The token approval is revoked by approving the zero address.
+        (COMMAND_REVOKE, Some(arg_matches)) => {
+            if arg_matches.is_present(COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR) {
+                return approve_all(
+                    &web3,
+                    SignerLoader::load(arg_matches)?,
+                    TransactionReceiptWaiter::new(arg_matches),
+                    ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                    ArgumentParser::parse_address(
+                        arg_matches,
+                        COMMAND_APPROVE_ALL_ARGUMENT_OPERATOR,
+                    )?,
+                    false,
+                );
+            }
+
+            return approve(
+                &web3,
+                SignerLoader::load(arg_matches)?,
+                TransactionReceiptWaiter::new(arg_matches),
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_token_id(arg_matches, COMMAND_QUERY_ARGUMENT_TOKEN_ID)?,
+                Address::zero(),
+            );
+        }
+        (COMMAND_COLLECTIONS, Some(arg_matches)) => match arg_matches.subcommand() {
+            (COMMAND_COLLECTIONS_LIST, Some(arg_matches_)) => {
+                return list_collections(
+                    &web3,
+                    resolve_to_address(&web3, arg_matches_, deployments_file_path)?,
+                    value_t_or_exit!(arg_matches_, COMMAND_COLLECTIONS_LIST_ARGUMENT_OFFSET, u64),
+                    match arg_matches_.is_present(COMMAND_COLLECTIONS_LIST_ARGUMENT_LIMIT) {
+                        true => Some(value_t_or_exit!(
+                            arg_matches_,
+                            COMMAND_COLLECTIONS_LIST_ARGUMENT_LIMIT,
+                            u64
+                        )),
+                        false => None,
+                    },
+                );
+            }
+            (COMMAND_COLLECTIONS_EXISTS, Some(arg_matches_)) => {
+                return is_collection_exist(
+                    &web3,
+                    resolve_to_address(&web3, arg_matches_, deployments_file_path)?,
+                    ArgumentParser::parse_address(arg_matches_, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                );
+            }
+            _ => {}
+        },
+        (COMMAND_NAME, Some(arg_matches)) => {
+            return read_collection_text(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                "name",
+                "name",
+            );
+        }
+        (COMMAND_SYMBOL, Some(arg_matches)) => {
+            return read_collection_text(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                "symbol",
+                "symbol",
+            );
+        }
+        (COMMAND_OWNER_OF, Some(arg_matches)) => {
+            return read_owner(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_token_id(arg_matches, COMMAND_QUERY_ARGUMENT_TOKEN_ID)?,
+            );
+        }
+        (COMMAND_TOKEN_URI, Some(arg_matches)) => {
+            return read_token_uri(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_token_id(arg_matches, COMMAND_QUERY_ARGUMENT_TOKEN_ID)?,
+            );
+        }
+        (COMMAND_BALANCE_OF, Some(arg_matches)) => {
+            return read_balance(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_OWNER)?,
+            );
+        }
+        (COMMAND_GET_METHOD_CALLER, Some(arg_matches)) => {
+            let from = match arg_matches.is_present(FROM_ADRESS) {
+                true => Some(ArgumentParser::parse_address(arg_matches, FROM_ADRESS)?),
+                false => None,
+            };
+
+            return read_method_caller(
+                &web3,
+                ArgumentParser::parse_address(arg_matches, COMMAND_QUERY_ARGUMENT_COLLECTION)?,
+                from,
+            );
+        }
+        _ => {}
+    }
+
+    return Err(Error::Config {
+        context: "Invalid command".to_string(),
+        source: None,
+    });
+}
+
+// The flag belongs to the innermost subcommand.
+fn is_json_requested<'a>(matches: &'a ArgMatches<'a>) -> bool {
+    if matches.is_present(JSON) {
+        return true;
+    }
+
+    match matches.subcommand() {
+        (_, Some(arg_matches)) => {
+            return is_json_requested(arg_matches);
+        }
+        (_, None) => {
+            return false;
+        }
+    }
+}
+
+// The address is read from the deployments file by the chain id of the node, unless it is given.
+fn resolve_to_address<'a>(
+    web3: &'a Web3<Http>,
+    arg_matches: &'a ArgMatches<'a>,
+    deployments_file_path: &'a str,
+) -> Result<Address, Error> {
+    if arg_matches.is_present(TO_ADDRESS) {
+        return ArgumentParser::parse_address(arg_matches, TO_ADDRESS);
+    }
+
+    return DeploymentRegistry::read_address(
+        Path::new(deployments_file_path),
+        read_chain_id(web3)?,
+    );
+}
+
+fn validate_quantity(value: String) -> Result<(), String> {
+    match u64::from_str(value.as_str()) {
+        Ok(_) => {
+            return Ok(());
+        }
+        Err(_) => {
+            return Err("The value must be a non-negative integer.".to_string());
+        }
+    }
+}
+
+fn create_to_argument<'a, 'b>() -> Arg<'a, 'b> {
+    return Arg::with_name(TO_ADDRESS)
+        .long(TO_ADDRESS)
+        .takes_value(true)
+        .value_name("ADDRESS")
+        .help("Called contract address, the deployed CollectionAggregator by default.");
+}
+
+fn create_collection_argument<'a, 'b>() -> Arg<'a, 'b> {
+    return Arg::with_name(COMMAND_QUERY_ARGUMENT_COLLECTION)
+        .index(1)
+        .takes_value(true)
+        .required(true)
+        .help("ERC721 contract address.");
+}
+
+fn create_token_id_argument<'a, 'b>() -> Arg<'a, 'b> {
+    return Arg::with_name(COMMAND_QUERY_ARGUMENT_TOKEN_ID)
+        .index(2)
+        .takes_value(true)
+        .required(true)
+        .help("Decimal token id.");
+}
 
-            let from_ = match Address::from_str(from.as_str()) {
-                Ok(to__) => to__,
+fn add_output_arguments<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    return app.arg(
+        Arg::with_name(JSON)
+            .long(JSON)
+            .help("Print the result as JSON."),
+    );
+}
+
+// Exactly one signer is required: the account that is unlocked in the node, or the local key.
+fn add_transaction_arguments<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    return add_output_arguments(app)
+        .arg(
+            Arg::with_name(FROM_ADRESS)
+                .long(FROM_ADRESS)
+                .takes_value(true)
+                .value_name("ADDRESS")
+                .help("Sender address, which is unlocked in the node."),
+        )
+        .arg(
+            Arg::with_name(SIGNER_PRIVATE_KEY)
+                .long(SIGNER_PRIVATE_KEY)
+                .takes_value(true)
+                .value_name("HEX")
+                .env("ERC721_PRIVATE_KEY")
+                .hide_env_values(true)
+                .help("Private key of the sender."),
+        )
+        .arg(
+            Arg::with_name(SIGNER_KEYSTORE)
+                .long(SIGNER_KEYSTORE)
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Keystore file of the sender. The passphrase is prompted."),
+        )
+        .arg(
+            Arg::with_name(SIGNER_MNEMONIC)
+                .long(SIGNER_MNEMONIC)
+                .takes_value(true)
+                .value_name("PHRASE")
+                .env("ERC721_MNEMONIC")
+                .hide_env_values(true)
+                .help("BIP-39 mnemonic of the sender."),
+        )
+        .arg(
+            Arg::with_name(SIGNER_DERIVATION_PATH)
+                .long(SIGNER_DERIVATION_PATH)
+                .takes_value(true)
+                .value_name("PATH")
+                .default_value(SIGNER_DEFAULT_DERIVATION_PATH)
+                .help("Derivation path of the mnemonic key."),
+        )
+        .group(
+            ArgGroup::with_name(SIGNER)
+                .args(&[
+                    FROM_ADRESS,
+                    SIGNER_PRIVATE_KEY,
+                    SIGNER_KEYSTORE,
+                    SIGNER_MNEMONIC,
+                ])
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(RECEIPT_CONFIRMATIONS)
+                .long(RECEIPT_CONFIRMATIONS)
+                .takes_value(true)
+                .value_name("QUANTITY")
+                .default_value("1")
+                .validator(|value| match u64::from_str(value.as_str()) {
+                    Ok(quantity) if quantity >= 1 => {
+                        return Ok(());
+                    }
+                    _ => {
+                        return Err("The quantity must be a positive integer.".to_string());
+                    }
+                })
+                .help("Blocks that include or follow the transaction before it is reported."),
+        )
+        .arg(
+            Arg::with_name(RECEIPT_TIMEOUT)
+                .long(RECEIPT_TIMEOUT)
+                .takes_value(true)
+                .value_name("SECONDS")
+                .default_value("120")
+                .validator(validate_quantity)
+                .help("Time to wait for the confirmed receipt."),
+        );
+}
+
+// The node signs the transaction of its unlocked account. The local key signs it before it is sent.
+enum Signer {
+    Node(Address),
+    Local(SecretKey),
+}
+
+impl Signer {
+    fn get_address(&self) -> Address {
+        match *self {
+            Self::Node(address) => {
+                return address;
+            }
+            Self::Local(ref secret_key) => {
+                return secret_key.address();
+            }
+        }
+    }
+}
+
+struct SignerLoader;
+
+impl SignerLoader {
+    fn load<'a>(arg_matches: &'a ArgMatches<'a>) -> Result<Signer, Error> {
+        if let Some(from) = arg_matches.value_of(FROM_ADRESS) {
+            match Address::from_str(from) {
+                Ok(from_) => {
+                    return Ok(Signer::Node(from_));
+                }
                 Err(error) => {
-                    println!("{}", &error);
+                    return Err(Error::Config {
+                        context: format!("Invalid sender address '{}'", from),
+                        source: Some(Box::from(error)),
+                    });
+                }
+            }
+        }
 
-                    return ();
+        if let Some(private_key) = arg_matches.value_of(SIGNER_PRIVATE_KEY) {
+            return Ok(Signer::Local(Self::load_from_private_key(private_key)?));
+        }
+
+        if let Some(keystore_file_path) = arg_matches.value_of(SIGNER_KEYSTORE) {
+            return Ok(Signer::Local(Self::load_from_keystore(Path::new(
+                keystore_file_path,
+            ))?));
+        }
+
+        if let Some(mnemonic) = arg_matches.value_of(SIGNER_MNEMONIC) {
+            return Ok(Signer::Local(Self::load_from_mnemonic(
+                mnemonic,
+                arg_matches
+                    .value_of(SIGNER_DERIVATION_PATH)
+                    .unwrap_or(SIGNER_DEFAULT_DERIVATION_PATH),
+            )?));
+        }
+
+        return Err(Error::Config {
+            context: "The signer is not specified".to_string(),
+            source: None,
+        });
+    }
+
+    // The key is hex, with or without the '0x' prefix.
+    fn load_from_private_key<'a>(private_key: &'a str) -> Result<SecretKey, Error> {
+        let private_key_ = private_key.trim();
+
+        match SecretKey::from_str(private_key_.strip_prefix("0x").unwrap_or(private_key_)) {
+            Ok(secret_key) => {
+                return Ok(secret_key);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: "Invalid private key".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    // The keystore is the Web3 Secret Storage (V3) JSON file.
+    fn load_from_keystore<'a>(keystore_file_path: &'a Path) -> Result<SecretKey, Error> {
+        let passphrase = match rpassword::prompt_password(format!(
+            "Passphrase of '{}': ",
+            keystore_file_path.display()
+        )) {
+            Ok(passphrase_) => passphrase_,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: "Failed to read the keystore passphrase".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        return Self::decrypt_keystore(keystore_file_path, passphrase.as_str());
+    }
+
+    fn decrypt_keystore<'a>(
+        keystore_file_path: &'a Path,
+        passphrase: &'a str,
+    ) -> Result<SecretKey, Error> {
+        let private_key = match eth_keystore::decrypt_key(keystore_file_path, passphrase) {
+            Ok(private_key_) => private_key_,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to decrypt the keystore '{}'",
+                        keystore_file_path.display()
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        match SecretKey::from_slice(private_key.as_slice()) {
+            Ok(secret_key) => {
+                return Ok(secret_key);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: "Invalid private key in the keystore".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    // The mnemonic is English and has no passphrase.
+    fn load_from_mnemonic<'a>(
+        mnemonic: &'a str,
+        derivation_path: &'a str,
+    ) -> Result<SecretKey, Error> {
+        let mnemonic_ = match Mnemonic::parse_in_normalized(Language::English, mnemonic.trim()) {
+            Ok(mnemonic__) => mnemonic__,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: "Invalid mnemonic".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let derivation_path_ = match DerivationPath::from_str(derivation_path) {
+            Ok(derivation_path__) => derivation_path__,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!("Invalid derivation path '{}'", derivation_path),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let extended_private_key =
+            match XPrv::derive_from_path(mnemonic_.to_seed(""), &derivation_path_) {
+                Ok(extended_private_key_) => extended_private_key_,
+                Err(error) => {
+                    return Err(Error::Config {
+                        context: format!("Failed to derive the key of '{}'", derivation_path),
+                        source: Some(Box::from(error)),
+                    });
                 }
             };
 
-            let to = value_t_or_exit!(arg_matches, TO_ADDRESS, String);
+        match SecretKey::from_slice(&extended_private_key.to_bytes()) {
+            Ok(secret_key) => {
+                return Ok(secret_key);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: "Invalid derived private key".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+}
 
-            let to_ = match Address::from_str(to.as_str()) {
-                Ok(to__) => to__,
-                Err(error) => {
-                    println!("{}", &error);
+// The nonce of the local key is read from the pending transactions of the node once, and is counted
+// locally after that, so that the transactions sent one after another never take the same one.
+struct TransactionSender {
+    signer: Signer,
+    local_nonce: Mutex<Option<U256>>,
+}
 
-                    return ();
+impl TransactionSender {
+    fn new(signer: Signer) -> Self {
+        return Self {
+            signer,
+            local_nonce: Mutex::new(None),
+        };
+    }
+
+    async fn send<'a>(
+        &'a self,
+        web3: &'a Web3<Http>,
+        to: Option<Address>,
+        data: Vec<u8>,
+        gas: U256,
+    ) -> Result<H256, Error> {
+        let secret_key = match self.signer {
+            Signer::Node(from) => {
+                match web3
+                    .eth()
+                    .send_transaction(TransactionRequest {
+                        from,
+                        to,
+                        gas: Some(gas),
+                        data: Some(Bytes(data)),
+                        ..TransactionRequest::default()
+                    })
+                    .await
+                {
+                    Ok(tx_hash) => {
+                        return Ok(tx_hash);
+                    }
+                    Err(error) => {
+                        return Err(Error::Transport {
+                            context: "Failed to send the transaction".to_string(),
+                            source: error,
+                        });
+                    }
                 }
-            };
+            }
+            Signer::Local(ref secret_key_) => secret_key_,
+        };
 
-            let name =
-                value_t_or_exit!(arg_matches, COMMAND_CREATE_COLLECTION_ARGUMENT_NAME, String);
+        // The nonce is locked until the transaction is sent, so that no other one takes it.
+        let mut local_nonce = self.local_nonce.lock().await;
 
-            let symbol = value_t_or_exit!(
-                arg_matches,
-                COMMAND_CREATE_COLLECTION_ARGUMENT_SYMBOL,
-                String
-            );
+        let nonce = match *local_nonce {
+            Some(nonce_) => nonce_,
+            None => match web3
+                .eth()
+                .transaction_count(self.signer.get_address(), Some(BlockNumber::Pending))
+                .await
+            {
+                Ok(nonce_) => nonce_,
+                Err(error) => {
+                    return Err(Error::Transport {
+                        context: "Failed to read the nonce of the sender".to_string(),
+                        source: error,
+                    });
+                }
+            },
+        };
+
+        let signed_transaction = match web3
+            .accounts()
+            .sign_transaction(
+                TransactionParameters {
+                    nonce: Some(nonce),
+                    to,
+                    gas,
+                    data: Bytes(data),
+                    ..TransactionParameters::default()
+                },
+                secret_key,
+            )
+            .await
+        {
+            Ok(signed_transaction_) => signed_transaction_,
+            Err(error) => {
+                return Err(Error::Transport {
+                    context: "Failed to sign the transaction".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        match web3
+            .eth()
+            .send_raw_transaction(signed_transaction.raw_transaction)
+            .await
+        {
+            Ok(tx_hash) => {
+                *local_nonce = Some(nonce + 1);
 
-            create_collection(&web3, from_, to_, name, symbol)
+                return Ok(tx_hash);
+            }
+            // The node may have taken the nonce or not, so it is read again.
+            Err(error) => {
+                *local_nonce = None;
+
+                return Err(Error::Transport {
+                    context: "Failed to send the signed transaction".to_string(),
+                    source: error,
+                });
+            }
         }
-        (COMMAND_MINT, Some(arg_matches)) => {
-            let from = value_t_or_exit!(arg_matches, FROM_ADRESS, String);
+    }
+}
 
-            let from_ = match Address::from_str(from.as_str()) {
-                Ok(to__) => to__,
-                Err(error) => {
-                    println!("{}", &error);
+struct TransactionReceiptWaiter {
+    confirmation_quantity: u64,
+    timeout: Duration,
+}
 
-                    return ();
+impl TransactionReceiptWaiter {
+    // The arguments are validated by the parser.
+    fn new<'a>(arg_matches: &'a ArgMatches<'a>) -> Self {
+        return Self {
+            confirmation_quantity: value_t_or_exit!(arg_matches, RECEIPT_CONFIRMATIONS, u64),
+            timeout: Duration::from_secs(value_t_or_exit!(arg_matches, RECEIPT_TIMEOUT, u64)),
+        };
+    }
+
+    // The reverted transaction is an error, because it has no events to report.
+    async fn wait<'a>(
+        &'a self,
+        web3: &'a Web3<Http>,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, Error> {
+        let transaction_receipt = match time::timeout(self.timeout, self.poll(web3, tx_hash)).await
+        {
+            Ok(transaction_receipt_) => transaction_receipt_?,
+            Err(_) => {
+                return Err(Error::Transaction {
+                    context: format!(
+                        "The transaction {:?} is not confirmed in {} seconds",
+                        tx_hash,
+                        self.timeout.as_secs()
+                    ),
+                });
+            }
+        };
+
+        if transaction_receipt.status == Some(U64::zero()) {
+            return Err(Error::Transaction {
+                context: format!(
+                    "The transaction {:?} is reverted in the block {}",
+                    tx_hash,
+                    transaction_receipt.block_number.unwrap_or_default()
+                ),
+            });
+        }
+
+        return Ok(transaction_receipt);
+    }
+
+    async fn poll<'a>(
+        &'a self,
+        web3: &'a Web3<Http>,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, Error> {
+        loop {
+            let transaction_receipt = match web3.eth().transaction_receipt(tx_hash).await {
+                Ok(transaction_receipt_) => transaction_receipt_,
+                Err(error) => {
+                    return Err(Error::Transport {
+                        context: format!("Failed to read the receipt of {:?}", tx_hash),
+                        source: error,
+                    });
                 }
             };
 
-            let to = value_t_or_exit!(arg_matches, TO_ADDRESS, String);
+            // The receipt of the pending transaction has no block.
+            if let Some(transaction_receipt_) = transaction_receipt {
+                if let Some(block_number) = transaction_receipt_.block_number {
+                    let last_block_number = match web3.eth().block_number().await {
+                        Ok(last_block_number_) => last_block_number_,
+                        Err(error) => {
+                            return Err(Error::Transport {
+                                context: "Failed to read the last block number".to_string(),
+                                source: error,
+                            });
+                        }
+                    };
 
-            let to_ = match Address::from_str(to.as_str()) {
-                Ok(to__) => to__,
-                Err(error) => {
-                    println!("{}", &error);
+                    if last_block_number.as_u64() + 1
+                        >= block_number.as_u64() + self.confirmation_quantity
+                    {
+                        return Ok(transaction_receipt_);
+                    }
+                }
+            }
+
+            time::sleep(Duration::from_millis(RECEIPT_POLL_INTERVAL_MILLISECONDS)).await;
+        }
+    }
+}
+
+// The record is printed as its fields, one per line, and the table as its rows under the header.
+// With the '--json' flag they are printed as a JSON object and a JSON array of objects.
+enum Report {
+    Record(Vec<(&'static str, Value)>),
+    Table(Vec<&'static str>, Vec<Vec<Value>>),
+}
+
+impl Report {
+    fn from_transaction_receipt<'a>(transaction_receipt: &'a TransactionReceipt) -> Self {
+        return Self::Record(vec![
+            (
+                "tx_hash",
+                Value::from(format!("{:?}", transaction_receipt.transaction_hash)),
+            ),
+            (
+                "block_number",
+                Value::from(
+                    transaction_receipt
+                        .block_number
+                        .unwrap_or_default()
+                        .as_u64(),
+                ),
+            ),
+            (
+                "gas_used",
+                Value::from(transaction_receipt.gas_used.unwrap_or_default().low_u64()),
+            ),
+        ]);
+    }
+
+    fn push<'a>(&'a mut self, name: &'static str, value: Value) -> () {
+        if let Self::Record(ref mut field_registry) = *self {
+            field_registry.push((name, value));
+        }
+
+        return ();
+    }
+
+    fn print(&self, is_json: bool) -> () {
+        match *self {
+            Self::Record(ref field_registry) => {
+                if is_json {
+                    println!("{}", Self::create_object(field_registry.iter().cloned()));
 
                     return ();
                 }
-            };
 
-            let collection =
-                value_t_or_exit!(arg_matches, COMMAND_MINT_ARGUMENT_COLLECTION, String);
+                for (name, value) in field_registry.iter() {
+                    println!("{}: {}", name, Self::format(value));
+                }
+            }
+            Self::Table(ref column_registry, ref row_registry) => {
+                if is_json {
+                    let object_registry = row_registry
+                        .iter()
+                        .map(|row| {
+                            return Self::create_object(
+                                column_registry.iter().copied().zip(row.iter().cloned()),
+                            );
+                        })
+                        .collect::<Vec<Value>>();
 
-            let collection_ = match Address::from_str(collection.as_str()) {
-                Ok(collection__) => collection__,
-                Err(error) => {
-                    println!("{}", &error);
+                    println!("{}", Value::Array(object_registry));
 
                     return ();
                 }
-            };
 
-            let recipient = value_t_or_exit!(arg_matches, COMMAND_MINT_ARGUMENT_RECIPIENT, String);
+                let mut width_registry = column_registry
+                    .iter()
+                    .map(|column| column.len())
+                    .collect::<Vec<usize>>();
 
-            let recipient_ = match Address::from_str(recipient.as_str()) {
-                Ok(recipient__) => recipient__,
-                Err(error) => {
-                    println!("{}", &error);
+                for row in row_registry.iter() {
+                    for (width, value) in width_registry.iter_mut().zip(row.iter()) {
+                        *width = cmp::max(*width, Self::format(value).len());
+                    }
+                }
 
-                    return ();
+                Self::print_row(
+                    &width_registry,
+                    column_registry.iter().map(|column| column.to_string()),
+                );
+
+                for row in row_registry.iter() {
+                    Self::print_row(&width_registry, row.iter().map(Self::format));
                 }
-            };
+            }
+        }
 
-            let token_uri = value_t_or_exit!(arg_matches, COMMAND_MINT_ARGUMENT_TOKEN_URI, String);
+        return ();
+    }
 
-            mint(&web3, from_, to_, collection_, recipient_, token_uri)
+    fn print_row<'a, I>(width_registry: &'a [usize], cell_registry: I) -> ()
+    where
+        I: Iterator<Item = String>,
+    {
+        let line = width_registry
+            .iter()
+            .zip(cell_registry)
+            .map(|(width, cell)| format!("{:<1$}", cell, width))
+            .collect::<Vec<String>>()
+            .join("  ");
+
+        println!("{}", line.trim_end());
+
+        return ();
+    }
+
+    fn create_object<'a, I>(field_registry: I) -> Value
+    where
+        I: Iterator<Item = (&'a str, Value)>,
+    {
+        let mut map = Map::new();
+
+        for (name, value) in field_registry {
+            map.insert(name.to_string(), value);
         }
-        _ => {
-            println!("Invalid command");
 
-            return ();
+        return Value::Object(map);
+    }
+
+    // The strings are printed without quotes.
+    fn format<'a>(value: &'a Value) -> String {
+        match *value {
+            Value::String(ref value_) => {
+                return value_.clone();
+            }
+            _ => {
+                return value.to_string();
+            }
         }
-    };
+    }
+}
+
+struct ArgumentParser;
+
+impl ArgumentParser {
+    fn parse_address<'a>(arg_matches: &'a ArgMatches<'a>, name: &'a str) -> Result<Address, Error> {
+        let value = arg_matches.value_of(name).unwrap_or("");
+
+        match Address::from_str(value) {
+            Ok(address) => {
+                return Ok(address);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!("Invalid '{}' address '{}'", name, value),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
 
-    if let Err(error) = result {
-        println!("{}", &error);
+    fn parse_token_id<'a>(arg_matches: &'a ArgMatches<'a>, name: &'a str) -> Result<U256, Error> {
+        let value = arg_matches.value_of(name).unwrap_or("");
+
+        match U256::from_dec_str(value) {
+            Ok(token_id) => {
+                return Ok(token_id);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!("Invalid '{}' '{}', expected a decimal integer", name, value),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+}
+
+struct ContractQuerier;
+
+impl ContractQuerier {
+    // The call is made with 'eth_call', so the 'from' address is only seen by the contract.
+    async fn query<'a, R, P>(
+        web3: &'a Web3<Http>,
+        address: Address,
+        contract: &'a Contract,
+        function_name: &'a str,
+        parameters: P,
+        from: Option<Address>,
+    ) -> Result<R, Error>
+    where
+        R: Detokenize,
+        P: Tokenize,
+    {
+        match Web3Contract::new(web3.eth(), address, contract.clone())
+            .query::<R, _, _, _>(function_name, parameters, from, Options::default(), None)
+            .await
+        {
+            Ok(result) => {
+                return Ok(result);
+            }
+            Err(error) => {
+                return Err(Error::from_contract_error(
+                    format!("Failed to call '{}' of {:?}", function_name, address).as_str(),
+                    error,
+                ));
+            }
+        }
     }
+}
 
-    return ();
+struct ReceiptLogFinder;
+
+impl ReceiptLogFinder {
+    fn find<'a>(
+        transaction_receipt: &'a TransactionReceipt,
+        contract: &'a Contract,
+        address: Address,
+        event_name: &'a str,
+    ) -> Result<&'a Log, Error> {
+        let topic = EventTopicCreator::create_from_abi(contract, event_name)?;
+
+        match transaction_receipt.logs.iter().find(|log| {
+            return log.address == address && log.topics.first() == Some(&topic);
+        }) {
+            Some(log) => {
+                return Ok(log);
+            }
+            None => {
+                return Err(Error::NotFound {
+                    context: format!(
+                        "The receipt of {:?} has no '{}' event",
+                        transaction_receipt.transaction_hash, event_name
+                    ),
+                });
+            }
+        }
+    }
+}
+
+// The deployments file is the JSON object of the deployments keyed by the decimal chain id, like
+// {"1337": {"address": _, "tx_hash": _, "block_number": _}}.
+struct DeploymentRegistry;
+
+impl DeploymentRegistry {
+    fn load<'a>(deployments_file_path: &'a Path) -> Result<Map<String, Value>, Error> {
+        if !deployments_file_path.exists() {
+            return Ok(Map::new());
+        }
+
+        let deployments = match fs::read_to_string(deployments_file_path) {
+            Ok(deployments_) => deployments_,
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to read the deployments file '{}'",
+                        deployments_file_path.display()
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        match serde_json::from_str::<Map<String, Value>>(deployments.as_str()) {
+            Ok(deployment_registry) => {
+                return Ok(deployment_registry);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to parse the deployments file '{}'",
+                        deployments_file_path.display()
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    fn read_address<'a>(deployments_file_path: &'a Path, chain_id: U256) -> Result<Address, Error> {
+        let deployment_registry = Self::load(deployments_file_path)?;
+
+        let address = match deployment_registry
+            .get(chain_id.to_string().as_str())
+            .and_then(|deployment| deployment.get("address"))
+            .and_then(|address_| address_.as_str())
+        {
+            Some(address_) => address_,
+            None => {
+                return Err(Error::NotFound {
+                    context: format!(
+                        "No CollectionAggregator of the chain {} in the deployments file '{}', deploy it or give the '--{}' address",
+                        chain_id,
+                        deployments_file_path.display(),
+                        TO_ADDRESS
+                    ),
+                });
+            }
+        };
+
+        match Address::from_str(address) {
+            Ok(address_) => {
+                return Ok(address_);
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Invalid address '{}' of the chain {} in the deployments file '{}'",
+                        address,
+                        chain_id,
+                        deployments_file_path.display()
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+
+    // The deployment replaces the previous one of the chain and keeps the other chains.
+    fn save<'a>(
+        deployments_file_path: &'a Path,
+        chain_id: U256,
+        address: Address,
+        transaction_receipt: &'a TransactionReceipt,
+    ) -> Result<(), Error> {
+        let mut deployment_registry = Self::load(deployments_file_path)?;
+
+        let mut deployment = Map::new();
+        deployment.insert("address".to_string(), Value::from(format!("{:?}", address)));
+        deployment.insert(
+            "tx_hash".to_string(),
+            Value::from(format!("{:?}", transaction_receipt.transaction_hash)),
+        );
+        deployment.insert(
+            "block_number".to_string(),
+            Value::from(
+                transaction_receipt
+                    .block_number
+                    .unwrap_or_default()
+                    .as_u64(),
+            ),
+        );
+
+        deployment_registry.insert(chain_id.to_string(), Value::Object(deployment));
+
+        let deployments = match serde_json::to_string_pretty(&deployment_registry) {
+            Ok(deployments_) => deployments_,
+            Err(error) => {
+                return Err(Error::Serialization {
+                    context: "Failed to serialize the deployments".to_string(),
+                    source: error,
+                });
+            }
+        };
+
+        match fs::write(deployments_file_path, deployments + "\n") {
+            Ok(_) => {
+                return Ok(());
+            }
+            Err(error) => {
+                return Err(Error::Config {
+                    context: format!(
+                        "Failed to write the deployments file '{}'",
+                        deployments_file_path.display()
+                    ),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
+}
+
+struct GasEstimator;
+
+impl GasEstimator {
+    // The node estimates the gas by executing the transaction, so the one that reverts fails here.
+    async fn estimate<'a>(
+        web3: &'a Web3<Http>,
+        from: Address,
+        to: Option<Address>,
+        data: &'a [u8],
+    ) -> Result<U256, Error> {
+        match web3
+            .eth()
+            .estimate_gas(
+                CallRequest {
+                    from: Some(from),
+                    to,
+                    data: Some(Bytes(data.to_vec())),
+                    ..CallRequest::default()
+                },
+                None,
+            )
+            .await
+        {
+            Ok(gas) => {
+                return Ok(gas);
+            }
+            Err(error) => {
+                return Err(Error::Transport {
+                    context: "Failed to estimate the gas of the transaction".to_string(),
+                    source: error,
+                });
+            }
+        }
+    }
+}
+
+// The token is operated by its owner and the operators of the owner, and it is also transferred by
+// its approved address.
+struct TokenPermissionChecker;
+
+impl TokenPermissionChecker {
+    async fn check<'a>(
+        web3: &'a Web3<Http>,
+        contract: &'a Contract,
+        collection: Address,
+        token_id: U256,
+        sender: Address,
+        is_approved_allowed: bool,
+    ) -> Result<Address, Error> {
+        let owner = ContractQuerier::query::<Address, _>(
+            web3,
+            collection,
+            contract,
+            "ownerOf",
+            (token_id,),
+            None,
+        )
+        .await?;
+
+        if owner == sender {
+            return Ok(owner);
+        }
+
+        if ContractQuerier::query::<bool, _>(
+            web3,
+            collection,
+            contract,
+            "isApprovedForAll",
+            (owner, sender),
+            None,
+        )
+        .await?
+        {
+            return Ok(owner);
+        }
+
+        if is_approved_allowed {
+            let approved = ContractQuerier::query::<Address, _>(
+                web3,
+                collection,
+                contract,
+                "getApproved",
+                (token_id,),
+                None,
+            )
+            .await?;
+
+            if approved == sender {
+                return Ok(owner);
+            }
+        }
+
+        return Err(Error::Transaction {
+            context: format!(
+                "The sender {:?} is neither the owner {:?} of the token {} of {:?} nor approved",
+                sender, owner, token_id, collection
+            ),
+        });
+    }
+}
+
+struct CallDataEncoder;
+
+impl CallDataEncoder {
+    fn encode<'a, P>(
+        contract: &'a Contract,
+        function_name: &'a str,
+        parameters: P,
+    ) -> Result<Vec<u8>, Error>
+    where
+        P: Tokenize,
+    {
+        let function_registry = match contract.functions_by_name(function_name) {
+            Ok(function_registry_) => function_registry_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!("The contract has no '{}' function", function_name),
+                    source: Some(Box::from(error)),
+                });
+            }
+        };
+
+        let token_registry = parameters.into_tokens();
+
+        // The overloaded functions, like 'safeTransferFrom', differ by the quantity of the parameters.
+        let function = match function_registry
+            .iter()
+            .find(|function_| function_.inputs.len() == token_registry.len())
+        {
+            Some(function_) => function_,
+            None => {
+                return Err(Error::Abi {
+                    context: format!(
+                        "The contract has no '{}' function of {} parameters",
+                        function_name,
+                        token_registry.len()
+                    ),
+                    source: None,
+                });
+            }
+        };
+
+        match function.encode_input(token_registry.as_slice()) {
+            Ok(data) => {
+                return Ok(data);
+            }
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: format!("Failed to encode the '{}' call", function_name),
+                    source: Some(Box::from(error)),
+                });
+            }
+        }
+    }
 }
 
 #[tokio::main]
-async fn create_collection<'a>(
+async fn read_chain_id<'a>(web3: &'a Web3<Http>) -> Result<U256, Error> {
+    match web3.eth().chain_id().await {
+        Ok(chain_id) => {
+            return Ok(chain_id);
+        }
+        Err(error) => {
+            return Err(Error::Transport {
+                context: "Failed to read the chain id".to_string(),
+                source: error,
+            });
+        }
+    }
+}
+
+#[tokio::main]
+async fn deploy<'a>(
     web3: &'a Web3<Http>,
-    from: Address,
-    to: Address,
-    name: String,
-    symbol: String,
-) -> Result<(), Box<dyn Error + 'static>> {
-    let contract_abi = get_contract_abi(CONTRACT_METADATA_FILE_PATH)?;
+    artifact_file_path: &'a str,
+    deployments_file_path: &'a str,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
+) -> Result<Report, Error> {
+    let contract = ContractAbiLoader::load_from_artifact(Path::new(artifact_file_path))?;
+
+    let bytecode = ContractBytecodeLoader::load_from_artifact(Path::new(artifact_file_path))?;
+
+    // The constructor has no parameters, but its encoding follows the ABI of the artifact.
+    let data = match contract.constructor {
+        Some(ref constructor) => match constructor.encode_input(bytecode, &[]) {
+            Ok(data_) => data_,
+            Err(error) => {
+                return Err(Error::Abi {
+                    context: "Failed to encode the constructor call".to_string(),
+                    source: Some(Box::from(error)),
+                });
+            }
+        },
+        None => bytecode,
+    };
 
-    let contract = match Contract::from_json(web3.eth(), to, contract_abi.as_bytes()) {
-        Ok(contract_) => contract_,
+    let chain_id = match web3.eth().chain_id().await {
+        Ok(chain_id_) => chain_id_,
         Err(error) => {
-            return Err(Box::from(error));
+            return Err(Error::Transport {
+                context: "Failed to read the chain id".to_string(),
+                source: error,
+            });
         }
     };
 
-    let options = Options {
-        gas: Some(3000000.into()),
-        ..Options::default()
+    let gas = GasEstimator::estimate(web3, signer.get_address(), None, &data).await?;
+
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender.send(web3, None, data, gas).await?;
+
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let address = match transaction_receipt.contract_address {
+        Some(address_) => address_,
+        None => {
+            return Err(Error::NotFound {
+                context: format!("The receipt of {:?} has no contract address", tx_hash),
+            });
+        }
     };
 
-    contract
-        .call("createCollection", (name, symbol), from, options)
-        .await?;
+    DeploymentRegistry::save(
+        Path::new(deployments_file_path),
+        chain_id,
+        address,
+        &transaction_receipt,
+    )?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push("address", Value::from(format!("{:?}", address)));
+    report.push("chain_id", Value::from(chain_id.low_u64()));
+
+    return Ok(report);
+}
+
+#[tokio::main]
+async fn create_collection<'a>(
+    web3: &'a Web3<Http>,
+    contract_metadata_file_path: &'a str,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
+    to: Address,
+    name: String,
+    symbol: String,
+) -> Result<Report, Error> {
+    let contract = ContractAbiLoader::load_from_artifact(Path::new(contract_metadata_file_path))?;
+
+    let data = CallDataEncoder::encode(&contract, "createCollection", (name, symbol))?;
+
+    let gas = GasEstimator::estimate(web3, signer.get_address(), Some(to), &data).await?;
 
-    return Ok(());
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender.send(web3, Some(to), data, gas).await?;
+
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let collection_created_event = ContractEventDecoder::decode_collection_created(
+        ReceiptLogFinder::find(&transaction_receipt, &contract, to, "CollectionCreated")?,
+    )?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push(
+        "collection",
+        Value::from(format!("{:?}", collection_created_event.collection)),
+    );
+    report.push("name", Value::from(collection_created_event.name));
+    report.push("symbol", Value::from(collection_created_event.symbol));
+
+    return Ok(report);
 }
 
 #[tokio::main]
 async fn mint<'a>(
     web3: &'a Web3<Http>,
-    from: Address,
+    contract_metadata_file_path: &'a str,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
     to: Address,
     collection: Address,
     recipient: Address,
     token_uri: String,
-) -> Result<(), Box<dyn Error + 'static>> {
-    let contract_abi = get_contract_abi(CONTRACT_METADATA_FILE_PATH)?;
+) -> Result<Report, Error> {
+    let contract = ContractAbiLoader::load_from_artifact(Path::new(contract_metadata_file_path))?;
 
-    let contract = match Contract::from_json(web3.eth(), to, contract_abi.as_bytes()) {
-        Ok(contract_) => contract_,
-        Err(error) => {
-            return Err(Box::from(error));
-        }
-    };
+    let data = CallDataEncoder::encode(&contract, "mint", (collection, recipient, token_uri))?;
 
-    let options = Options {
-        gas: Some(150000.into()),
-        ..Options::default()
-    };
+    let gas = GasEstimator::estimate(web3, signer.get_address(), Some(to), &data).await?;
+
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender.send(web3, Some(to), data, gas).await?;
+
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let token_minted_event = ContractEventDecoder::decode_token_minted(ReceiptLogFinder::find(
+        &transaction_receipt,
+        &contract,
+        to,
+        "TokenMinted",
+    )?)?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push(
+        "collection",
+        Value::from(format!("{:?}", token_minted_event.collection)),
+    );
+    report.push(
+        "recipient",
+        Value::from(format!("{:?}", token_minted_event.recipient)),
+    );
+    report.push(
+        "token_id",
+        Value::from(token_minted_event.token_id.to_string()),
+    );
+    report.push("token_uri", Value::from(token_minted_event.token_uri));
+
+    return Ok(report);
+}
 
-    contract
-        .call("mint", (collection, recipient, token_uri), from, options)
+// The safe transfer checks that the contract recipient accepts the token.
+#[tokio::main]
+async fn transfer<'a>(
+    web3: &'a Web3<Http>,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
+    collection: Address,
+    token_id: U256,
+    recipient: Address,
+) -> Result<Report, Error> {
+    if recipient.is_zero() {
+        return Err(Error::Config {
+            context: "The recipient is the zero address".to_string(),
+            source: None,
+        });
+    }
+
+    let contract = ContractAbiLoader::load_collection()?;
+
+    let owner = TokenPermissionChecker::check(
+        web3,
+        contract,
+        collection,
+        token_id,
+        signer.get_address(),
+        true,
+    )
+    .await?;
+
+    let data = CallDataEncoder::encode(contract, "safeTransferFrom", (owner, recipient, token_id))?;
+
+    let gas = GasEstimator::estimate(web3, signer.get_address(), Some(collection), &data).await?;
+
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender
+        .send(web3, Some(collection), data, gas)
         .await?;
 
-    return Ok(());
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let transfer_event = ContractEventDecoder::decode_transfer(ReceiptLogFinder::find(
+        &transaction_receipt,
+        contract,
+        collection,
+        "Transfer",
+    )?)?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push(
+        "collection",
+        Value::from(format!("{:?}", transfer_event.collection)),
+    );
+    report.push("from", Value::from(format!("{:?}", transfer_event.from)));
+    report.push("to", Value::from(format!("{:?}", transfer_event.to)));
+    report.push("token_id", Value::from(transfer_event.token_id.to_string()));
+
+    return Ok(report);
 }
 
-fn get_contract_abi<'a>(file_path: &'a str) -> Result<String, Box<dyn Error + 'static>> {
-    let abi_file = match File::open(file_path) {
-        Ok(abi_file_) => abi_file_,
-        Err(error) => {
-            return Err(Box::from(error));
+// The zero approved address revokes the approval of the token.
+#[tokio::main]
+async fn approve<'a>(
+    web3: &'a Web3<Http>,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
+    collection: Address,
+    token_id: U256,
+    approved: Address,
+) -> Result<Report, Error> {
+    let contract = ContractAbiLoader::load_collection()?;
+
+    let owner = TokenPermissionChecker::check(
+        web3,
+        contract,
+        collection,
+        token_id,
+        signer.get_address(),
+        false,
+    )
+    .await?;
+
+    if approved == owner {
+        return Err(Error::Config {
+            context: format!("The approved address {:?} is the owner", approved),
+            source: None,
+        });
+    }
+
+    if approved.is_zero() {
+        let approved_ = ContractQuerier::query::<Address, _>(
+            web3,
+            collection,
+            contract,
+            "getApproved",
+            (token_id,),
+            None,
+        )
+        .await?;
+
+        if approved_.is_zero() {
+            return Err(Error::Transaction {
+                context: format!(
+                    "The token {} of {:?} has no approved address",
+                    token_id, collection
+                ),
+            });
         }
+    }
+
+    let data = CallDataEncoder::encode(contract, "approve", (approved, token_id))?;
+
+    let gas = GasEstimator::estimate(web3, signer.get_address(), Some(collection), &data).await?;
+
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender
+        .send(web3, Some(collection), data, gas)
+        .await?;
+
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let approval_event = ContractEventDecoder::decode_approval(ReceiptLogFinder::find(
+        &transaction_receipt,
+        contract,
+        collection,
+        "Approval",
+    )?)?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push(
+        "collection",
+        Value::from(format!("{:?}", approval_event.collection)),
+    );
+    report.push("owner", Value::from(format!("{:?}", approval_event.owner)));
+    report.push(
+        "approved",
+        Value::from(format!("{:?}", approval_event.approved)),
+    );
+    report.push("token_id", Value::from(approval_event.token_id.to_string()));
+
+    return Ok(report);
+}
+
+// The operator is approved or revoked for all the tokens of the sender in the collection.
+#[tokio::main]
+async fn approve_all<'a>(
+    web3: &'a Web3<Http>,
+    signer: Signer,
+    transaction_receipt_waiter: TransactionReceiptWaiter,
+    collection: Address,
+    operator: Address,
+    is_approved: bool,
+) -> Result<Report, Error> {
+    let contract = ContractAbiLoader::load_collection()?;
+
+    let owner = signer.get_address();
+
+    if operator == owner {
+        return Err(Error::Config {
+            context: format!("The operator {:?} is the sender", operator),
+            source: None,
+        });
+    }
+
+    let is_approved_ = ContractQuerier::query::<bool, _>(
+        web3,
+        collection,
+        contract,
+        "isApprovedForAll",
+        (owner, operator),
+        None,
+    )
+    .await?;
+
+    if is_approved_ == is_approved {
+        return Err(Error::Transaction {
+            context: format!(
+                "The operator {:?} of {:?} in {:?} is {}",
+                operator,
+                owner,
+                collection,
+                match is_approved {
+                    true => "already approved",
+                    false => "not approved",
+                }
+            ),
+        });
+    }
+
+    let data = CallDataEncoder::encode(contract, "setApprovalForAll", (operator, is_approved))?;
+
+    let gas = GasEstimator::estimate(web3, owner, Some(collection), &data).await?;
+
+    let transaction_sender = TransactionSender::new(signer);
+
+    let tx_hash = transaction_sender
+        .send(web3, Some(collection), data, gas)
+        .await?;
+
+    let transaction_receipt = transaction_receipt_waiter.wait(web3, tx_hash).await?;
+
+    let approval_for_all_event = ContractEventDecoder::decode_approval_for_all(
+        ReceiptLogFinder::find(&transaction_receipt, contract, collection, "ApprovalForAll")?,
+    )?;
+
+    let mut report = Report::from_transaction_receipt(&transaction_receipt);
+    report.push(
+        "collection",
+        Value::from(format!("{:?}", approval_for_all_event.collection)),
+    );
+    report.push(
+        "owner",
+        Value::from(format!("{:?}", approval_for_all_event.owner)),
+    );
+    report.push(
+        "operator",
+        Value::from(format!("{:?}", approval_for_all_event.operator)),
+    );
+    report.push("approved", Value::from(approval_for_all_event.approved));
+
+    return Ok(report);
+}
+
+#[tokio::main]
+async fn list_collections<'a>(
+    web3: &'a Web3<Http>,
+    to: Address,
+    offset: u64,
+    limit: Option<u64>,
+) -> Result<Report, Error> {
+    let length = CollectionRegistryReader::read_length(web3, to).await?;
+
+    let first_index = U256::from(offset);
+
+    let last_index = match limit {
+        Some(limit_) => cmp::min(first_index.saturating_add(U256::from(limit_)), length),
+        None => length,
     };
 
-    let json_value = match serde_json::from_reader::<_, Value>(abi_file) {
-        Ok(json_file_) => json_file_,
-        Err(error) => {
-            return Err(Box::from(error));
+    let mut row_registry: Vec<Vec<Value>> = vec![];
+
+    let mut index = first_index;
+
+    while index < last_index {
+        let collection = CollectionRegistryReader::read_by_index(web3, to, index).await?;
+
+        let (name, symbol) = CollectionMetadataReader::read(web3, collection).await?;
+
+        row_registry.push(vec![
+            Value::from(index.low_u64()),
+            Value::from(format!("{:?}", collection)),
+            Value::from(name),
+            Value::from(symbol),
+        ]);
+
+        index = index + 1;
+    }
+
+    return Ok(Report::Table(
+        vec!["index", "collection", "name", "symbol"],
+        row_registry,
+    ));
+}
+
+#[tokio::main]
+async fn is_collection_exist<'a>(
+    web3: &'a Web3<Http>,
+    to: Address,
+    collection: Address,
+) -> Result<Report, Error> {
+    let is_exist = CollectionRegistryReader::is_exist(web3, to, collection).await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        ("exists", Value::from(is_exist)),
+    ]));
+}
+
+// Reads the string that the function of the collection returns without parameters, like the name.
+#[tokio::main]
+async fn read_collection_text<'a>(
+    web3: &'a Web3<Http>,
+    collection: Address,
+    function_name: &'a str,
+    field_name: &'static str,
+) -> Result<Report, Error> {
+    let text = ContractQuerier::query::<String, _>(
+        web3,
+        collection,
+        ContractAbiLoader::load_collection()?,
+        function_name,
+        (),
+        None,
+    )
+    .await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        (field_name, Value::from(text)),
+    ]));
+}
+
+#[tokio::main]
+async fn read_owner<'a>(
+    web3: &'a Web3<Http>,
+    collection: Address,
+    token_id: U256,
+) -> Result<Report, Error> {
+    let owner = ContractQuerier::query::<Address, _>(
+        web3,
+        collection,
+        ContractAbiLoader::load_collection()?,
+        "ownerOf",
+        (token_id,),
+        None,
+    )
+    .await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        ("token_id", Value::from(token_id.to_string())),
+        ("owner", Value::from(format!("{:?}", owner))),
+    ]));
+}
+
+#[tokio::main]
+async fn read_token_uri<'a>(
+    web3: &'a Web3<Http>,
+    collection: Address,
+    token_id: U256,
+) -> Result<Report, Error> {
+    let token_uri = ContractQuerier::query::<String, _>(
+        web3,
+        collection,
+        ContractAbiLoader::load_collection()?,
+        "tokenURI",
+        (token_id,),
+        None,
+    )
+    .await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        ("token_id", Value::from(token_id.to_string())),
+        ("token_uri", Value::from(token_uri)),
+    ]));
+}
+
+#[tokio::main]
+async fn read_balance<'a>(
+    web3: &'a Web3<Http>,
+    collection: Address,
+    owner: Address,
+) -> Result<Report, Error> {
+    let balance = ContractQuerier::query::<U256, _>(
+        web3,
+        collection,
+        ContractAbiLoader::load_collection()?,
+        "balanceOf",
+        (owner,),
+        None,
+    )
+    .await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        ("owner", Value::from(format!("{:?}", owner))),
+        ("balance", Value::from(balance.to_string())),
+    ]));
+}
+
+#[tokio::main]
+async fn read_method_caller<'a>(
+    web3: &'a Web3<Http>,
+    collection: Address,
+    from: Option<Address>,
+) -> Result<Report, Error> {
+    let caller = ContractQuerier::query::<Address, _>(
+        web3,
+        collection,
+        ContractAbiLoader::load_collection()?,
+        "getMethodCaller",
+        (),
+        from,
+    )
+    .await?;
+
+    return Ok(Report::Record(vec![
+        ("collection", Value::from(format!("{:?}", collection))),
+        ("caller", Value::from(format!("{:?}", caller))),
+    ]));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::sync::Arc;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use tokio::net::TcpStream;
+    use web3::signing::keccak256;
+
+    // The first accounts of the 'test test ... junk' mnemonic of the local development nodes.
+    const MNEMONIC: &'static str = "test test test test test test test test test test test junk";
+    const PRIVATE_KEY: &'static str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS: &'static str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    const SECOND_ADDRESS: &'static str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    // Answers the JSON-RPC requests of the transaction sender and keeps their methods and parameters.
+    async fn serve_node(
+        stream: TcpStream,
+        request_registry: Arc<Mutex<Vec<(String, Value)>>>,
+    ) -> () {
+        let (mut reader, mut writer) = stream.into_split();
+
+        let mut buffer: Vec<u8> = vec![];
+
+        let mut chunk = [0_u8; 4096];
+
+        loop {
+            let header_end = match buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                Some(header_end_) => header_end_ + 4,
+                None => {
+                    match reader.read(&mut chunk).await {
+                        Ok(0) | Err(_) => {
+                            return ();
+                        }
+                        Ok(size) => buffer.extend_from_slice(&chunk[..size]),
+                    }
+
+                    continue;
+                }
+            };
+
+            let header = String::from_utf8_lossy(&buffer[..header_end]).to_lowercase();
+
+            let content_length = header
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|content_length_| usize::from_str(content_length_.trim()).ok())
+                .unwrap_or(0);
+
+            if buffer.len() < header_end + content_length {
+                match reader.read(&mut chunk).await {
+                    Ok(0) | Err(_) => {
+                        return ();
+                    }
+                    Ok(size) => buffer.extend_from_slice(&chunk[..size]),
+                }
+
+                continue;
+            }
+
+            let request =
+                serde_json::from_slice::<Value>(&buffer[header_end..header_end + content_length])
+                    .unwrap();
+
+            buffer.drain(..header_end + content_length);
+
+            let method = request["method"].as_str().unwrap().to_string();
+
+            let result = match method.as_str() {
+                "eth_getTransactionCount" => json!("0x5"),
+                "eth_gasPrice" | "eth_chainId" => json!("0x1"),
+                "net_version" => json!("1"),
+                "eth_sendRawTransaction" => json!(format!(
+                    "{:?}",
+                    H256(keccak256(request["params"][0].as_str().unwrap().as_bytes()))
+                )),
+                _ => Value::Null,
+            };
+
+            request_registry
+                .lock()
+                .await
+                .push((method, request["params"].clone()));
+
+            let body = json!({"jsonrpc": "2.0", "id": request["id"], "result": result}).to_string();
+
+            writer
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
         }
-    };
+    }
 
-    let json_value_abi = match json_value.get("abi") {
-        Some(json_value_abi_) => json_value_abi_,
-        None => {
-            return Err(Box::from(LogicError));
+    #[test]
+    fn load_private_key_with_or_without_prefix() -> () {
+        for private_key in [
+            PRIVATE_KEY,
+            PRIVATE_KEY.strip_prefix("0x").unwrap(),
+            format!(" {}\n", PRIVATE_KEY).as_str(),
+        ] {
+            assert_eq!(
+                SignerLoader::load_from_private_key(private_key)
+                    .map(Signer::Local)
+                    .unwrap()
+                    .get_address(),
+                Address::from_str(ADDRESS).unwrap()
+            );
         }
-    };
 
-    return Ok(json_value_abi.to_string());
-}
+        assert!(SignerLoader::load_from_private_key("0x1234").is_err());
 
-#[derive(Debug)]
-struct LogicError;
+        return ();
+    }
+
+    #[test]
+    fn load_mnemonic_at_derivation_path() -> () {
+        assert_eq!(
+            SignerLoader::load_from_mnemonic(MNEMONIC, SIGNER_DEFAULT_DERIVATION_PATH)
+                .map(Signer::Local)
+                .unwrap()
+                .get_address(),
+            Address::from_str(ADDRESS).unwrap()
+        );
+        assert_eq!(
+            SignerLoader::load_from_mnemonic(MNEMONIC, "m/44'/60'/0'/0/1")
+                .map(Signer::Local)
+                .unwrap()
+                .get_address(),
+            Address::from_str(SECOND_ADDRESS).unwrap()
+        );
+        // The last word is not the checksum of the others.
+        assert!(SignerLoader::load_from_mnemonic(
+            "test test test test test test test test test test test test",
+            SIGNER_DEFAULT_DERIVATION_PATH
+        )
+        .is_err());
+        assert!(SignerLoader::load_from_mnemonic(MNEMONIC, "m/x").is_err());
 
-impl Display for LogicError {
-    fn fmt<'a>(&'a self, formatter: &'a mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(formatter, "Logic error.")
+        return ();
     }
-}
 
-impl Error for LogicError {}
+    #[test]
+    fn decrypt_keystore_with_passphrase() -> () {
+        let keystore_directory_path =
+            env::temp_dir().join(format!("communicator_keystore_{}", std::process::id()));
+
+        fs::create_dir_all(keystore_directory_path.as_path()).unwrap();
+
+        let secret_key = SignerLoader::load_from_private_key(PRIVATE_KEY).unwrap();
+
+        eth_keystore::encrypt_key(
+            keystore_directory_path.as_path(),
+            &mut rand::thread_rng(),
+            &secret_key[..],
+            "passphrase",
+            Some("keystore.json"),
+        )
+        .unwrap();
+
+        let keystore_file_path = keystore_directory_path.join("keystore.json");
+
+        assert_eq!(
+            SignerLoader::decrypt_keystore(keystore_file_path.as_path(), "passphrase")
+                .map(Signer::Local)
+                .unwrap()
+                .get_address(),
+            Address::from_str(ADDRESS).unwrap()
+        );
+        assert!(SignerLoader::decrypt_keystore(keystore_file_path.as_path(), "other").is_err());
+
+        fs::remove_dir_all(keystore_directory_path.as_path()).unwrap();
+
+        return ();
+    }
+
+    // The transactions are the same but for the nonce, so they are signed differently.
+    #[tokio::test]
+    async fn send_counts_nonce_locally() -> () {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let node_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let request_registry: Arc<Mutex<Vec<(String, Value)>>> = Arc::new(Mutex::new(vec![]));
+
+        let request_registry_ = request_registry.clone();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_node(stream, request_registry_.clone()));
+            }
+        });
+
+        let web3 = Web3::new(Http::new(node_url.as_str()).unwrap());
+
+        let transaction_sender = TransactionSender::new(Signer::Local(
+            SignerLoader::load_from_private_key(PRIVATE_KEY).unwrap(),
+        ));
+
+        let mut tx_hash_registry: Vec<H256> = vec![];
+
+        for _ in 0..2 {
+            tx_hash_registry.push(
+                transaction_sender
+                    .send(
+                        &web3,
+                        Some(Address::repeat_byte(1)),
+                        vec![1, 2, 3],
+                        U256::from(21000),
+                    )
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        assert!(tx_hash_registry[0] != tx_hash_registry[1]);
+        assert_eq!(
+            *transaction_sender.local_nonce.lock().await,
+            Some(U256::from(7))
+        );
+
+        let request_registry__ = request_registry.lock().await;
+
+        assert_eq!(
+            request_registry__
+                .iter()
+                .filter(|(method, _)| method == "eth_getTransactionCount")
+                .count(),
+            1
+        );
+        assert_eq!(
+            request_registry__
+                .iter()
+                .filter(|(method, _)| method == "eth_sendRawTransaction")
+                .count(),
+            2
+        );
+
+        return ();
+    }
+}